@@ -11,6 +11,10 @@ use cid::Cid;
 use fvm_ipld_blockstore::{Blockstore, Buffered};
 use fvm_ipld_encoding::DAG_CBOR;
 use fvm_shared::commcid::{FIL_COMMITMENT_SEALED, FIL_COMMITMENT_UNSEALED};
+use fvm_shared::{IDENTITY_HASH, IPLD_RAW};
+
+const BLAKE2B_256: u64 = 0xb220;
+const BLAKE2B_LEN: u8 = 32;
 
 /// Wrapper around `Blockstore` to limit and have control over when values are written.
 /// This type is not threadsafe and can only be used in synchronous contexts.
@@ -112,7 +116,7 @@ fn cbor_read_header_buf<B: Read>(br: &mut B, scratch: &mut [u8]) -> anyhow::Resu
 /// Given a CBOR serialized IPLD buffer, read through all of it and return all the Links.
 /// This function is useful because it is quite a bit more fast than doing this recursively on a
 /// deserialized IPLD object.
-fn scan_for_links<B: Read + Seek, F>(buf: &mut B, mut callback: F) -> Result<()>
+pub(super) fn scan_for_links<B: Read + Seek, F>(buf: &mut B, mut callback: F) -> Result<()>
 where
     F: FnMut(Cid) -> anyhow::Result<()>,
 {
@@ -163,45 +167,60 @@ where
     Ok(())
 }
 
+/// How a CID found while traversing the state should be handled.
+pub(super) enum CidKind {
+    /// A non-truncated blake2b-256 raw/cbor block (code/state), stored in the blockstore.
+    Block,
+    /// A cbor identity CID. There's nothing to load, but the digest may embed links.
+    InlineCbor,
+    /// A raw identity CID (fake code CID) or a commitment. There's nothing to load or traverse.
+    Ignored,
+}
+
+/// Classifies a CID found while traversing the state, failing on any CID we don't know how to
+/// handle.
+pub(super) fn classify_cid(cid: &Cid) -> Result<CidKind> {
+    // TODO(M2): Make this not cbor specific.
+    Ok(match (cid.codec(), cid.hash().code(), cid.hash().size()) {
+        // Allow non-truncated blake2b-256 raw/cbor (code/state)
+        (IPLD_RAW | DAG_CBOR, BLAKE2B_256, BLAKE2B_LEN) => CidKind::Block,
+        // Ignore raw identity cids (fake code cids)
+        (IPLD_RAW, IDENTITY_HASH, _) => CidKind::Ignored,
+        // Copy links from cbor identity cids.
+        // We shouldn't be creating these at the moment, but lotus' vm.Copy supports them.
+        (DAG_CBOR, IDENTITY_HASH, _) => CidKind::InlineCbor,
+        // Ignore commitments (not even going to check the hash function.
+        (FIL_COMMITMENT_UNSEALED | FIL_COMMITMENT_SEALED, _, _) => CidKind::Ignored,
+        // Fail on anything else. We usually want to continue on error, but there's really no going
+        // back from here.
+        (codec, hash, length) => {
+            return Err(anyhow!(
+                "cid {cid} has unexpected codec ({codec}), hash ({hash}), or length ({length})"
+            ))
+        }
+    })
+}
+
 /// Copies the IPLD DAG under `root` from the cache to the base store.
 fn copy_rec<'a>(
     cache: &'a HashMap<Cid, Vec<u8>>,
     root: Cid,
     buffer: &mut Vec<(Cid, &'a [u8])>,
 ) -> Result<()> {
-    const DAG_RAW: u64 = 0x55;
-    const BLAKE2B_256: u64 = 0xb220;
-    const BLAKE2B_LEN: u8 = 32;
-    const IDENTITY: u64 = 0x0;
-
     // Differences from lotus (vm.Copy):
     // 1. We assume that if we don't have a block in our buffer, it must already be in the client
     //    and don't check. This should only happen if the client is missing state.
     // 2. We always write-back new blocks, even if the client already has them. We haven't noticed a
     //    perf impact.
 
-    // TODO(M2): Make this not cbor specific.
-    match (root.codec(), root.hash().code(), root.hash().size()) {
-        // Allow non-truncated blake2b-256 raw/cbor (code/state)
-        (DAG_RAW | DAG_CBOR, BLAKE2B_256, BLAKE2B_LEN) => (),
-        // Ignore raw identity cids (fake code cids)
-        (DAG_RAW, IDENTITY, _) => return Ok(()),
-        // Copy links from cbor identity cids.
-        // We shouldn't be creating these at the moment, but lotus' vm.Copy supports them.
-        (DAG_CBOR, IDENTITY, _) => {
+    match classify_cid(&root)? {
+        CidKind::Block => (),
+        CidKind::InlineCbor => {
             return scan_for_links(&mut Cursor::new(root.hash().digest()), |link| {
                 copy_rec(cache, link, buffer)
             })
         }
-        // Ignore commitments (not even going to check the hash function.
-        (FIL_COMMITMENT_UNSEALED | FIL_COMMITMENT_SEALED, _, _) => return Ok(()),
-        // Fail on anything else. We usually want to continue on error, but there's really no going
-        // back from here.
-        (codec, hash, length) => {
-            return Err(anyhow!(
-                "cid {root} has unexpected codec ({codec}), hash ({hash}), or length ({length})"
-            ))
-        }
+        CidKind::Ignored => return Ok(()),
     }
 
     // If we don't have the block, we assume it's already in the datastore.
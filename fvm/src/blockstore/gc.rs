@@ -0,0 +1,649 @@
+//! Garbage collection for blockstores holding FVM state.
+//!
+//! Collection happens in two phases:
+//!
+//! 1. Mark: starting from a set of live roots (usually state roots), walk the IPLD DAG
+//!    breadth-first, one level at a time. [`par_mark`] (and [`par_collect`]) load and scan each
+//!    level in parallel, but require a threadsafe blockstore.
+//! 2. Sweep: delete every block in the store that wasn't marked, in batches.
+//!
+//! If any reachable block is missing from the store, its descendants can't be marked. In-place
+//! collection refuses to sweep in that case, rather than delete blocks that may still be live.
+//!
+//! In-place collection requires the store to be quiescent: nothing may be written to it from the
+//! start of the mark phase until the sweep completes. Blocks written in the meantime won't have
+//! been marked, so they will be swept.
+//!
+//! Alternatively, [`collect_into`] performs a "moving" collection, copying every reachable block
+//! into a fresh blockstore. Blocks are written children-first, so an interrupted copy never leaves
+//! a block in the destination store without its children. The source store is left untouched and
+//! can simply be discarded afterwards.
+//!
+//! Only the CIDs the FVM itself produces are understood: blake2b-256 dag-cbor and raw blocks,
+//! identity CIDs, and piece/sector commitments. Collection fails on any other CID rather than
+//! risk sweeping blocks it couldn't traverse.
+
+use std::collections::HashSet;
+use std::io::Cursor;
+
+use anyhow::{bail, Context, Result};
+use cid::Cid;
+use fvm_ipld_blockstore::{Blockstore, Sweepable};
+use fvm_ipld_encoding::DAG_CBOR;
+use fvm_shared::IPLD_RAW;
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+
+use super::buffered::{classify_cid, scan_for_links, CidKind};
+
+/// Number of blocks copied between progress reports during a moving collection.
+const COPY_PROGRESS_INTERVAL: usize = 10_000;
+
+/// Number of unreachable blocks deleted at a time, and between progress reports, while sweeping.
+pub const SWEEP_BATCH_SIZE: usize = 10_000;
+
+/// Progress reported to the caller while garbage collecting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GcProgress {
+    /// Reported after each level of the DAG has been traversed.
+    Mark {
+        /// Number of reachable blocks found so far.
+        reachable: usize,
+        /// Number of blocks in the next level of the DAG, still to be visited.
+        pending: usize,
+    },
+    /// Reported periodically while copying reachable blocks, and once the copy completes.
+    Copy {
+        /// Number of blocks copied so far.
+        copied: usize,
+    },
+    /// Reported after each batch of unreachable blocks has been deleted.
+    Sweep {
+        /// Number of keys scanned.
+        scanned: usize,
+        /// Number of unreachable blocks deleted.
+        swept: usize,
+    },
+}
+
+/// Summary of a garbage collection run.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct GcStats {
+    /// Number of reachable blocks present in the store.
+    pub reachable: usize,
+    /// Number of reachable blocks that were not present in the store.
+    pub missing: usize,
+    /// Number of blocks deleted (in-place collection only).
+    pub swept: usize,
+    /// Number of blocks copied to the destination store (moving collection only).
+    pub copied: usize,
+}
+
+/// Marks all blocks reachable from `roots`, returning the set of marked CIDs along with the number
+/// of reachable blocks present in (and missing from) the store.
+///
+/// The marked set holds every CID reachable from `roots`, including identity CIDs, commitments,
+/// and missing blocks. It can be passed to [`sweep`] as-is.
+///
+/// Blocks are loaded and scanned sequentially, one level of the DAG at a time. Use [`par_mark`] to
+/// load each level in parallel.
+pub fn mark<BS, I, P>(bs: &BS, roots: I, progress: P) -> Result<(HashSet<Cid>, GcStats)>
+where
+    BS: Blockstore,
+    I: IntoIterator<Item = Cid>,
+    P: FnMut(GcProgress),
+{
+    walk(
+        roots,
+        |level: &[Cid]| level.iter().map(|c| visit(bs, *c)).collect(),
+        progress,
+    )
+}
+
+/// Like [`mark`], but loads and scans each level of the DAG in parallel.
+pub fn par_mark<BS, I, P>(bs: &BS, roots: I, progress: P) -> Result<(HashSet<Cid>, GcStats)>
+where
+    BS: Blockstore + Sync,
+    I: IntoIterator<Item = Cid>,
+    P: FnMut(GcProgress),
+{
+    walk(
+        roots,
+        |level: &[Cid]| level.par_iter().map(|c| visit(bs, *c)).collect(),
+        progress,
+    )
+}
+
+/// Deletes every block in `bs` that isn't in `marked`, returning the number of deleted blocks.
+///
+/// Blocks are deleted in batches of [`SWEEP_BATCH_SIZE`], reporting progress after each batch.
+///
+/// The caller must ensure that:
+///
+/// - `marked` was computed by a mark phase that found no missing blocks. The children of a missing
+///   block can't be discovered, so any of them still in the store would be deleted.
+/// - The store isn't written to between marking and sweeping. Any block written after `marked` was
+///   computed is deleted too.
+pub fn sweep<BS, P>(bs: &BS, marked: &HashSet<Cid>, mut progress: P) -> Result<usize>
+where
+    BS: Sweepable,
+    P: FnMut(GcProgress),
+{
+    let mut scanned = 0;
+    let mut swept = 0;
+    let mut dead = Vec::with_capacity(SWEEP_BATCH_SIZE);
+    let mut delete = |dead: &mut Vec<Cid>, scanned: usize| -> Result<()> {
+        swept += dead.len();
+        bs.delete_many_keyed(dead.drain(..))
+            .context("failed to delete unreachable blocks")?;
+        progress(GcProgress::Sweep { scanned, swept });
+        Ok(())
+    };
+
+    bs.for_each_key(|k| {
+        scanned += 1;
+        if !marked.contains(k) {
+            dead.push(*k);
+            if dead.len() == SWEEP_BATCH_SIZE {
+                delete(&mut dead, scanned)?;
+            }
+        }
+        Ok(())
+    })?;
+    delete(&mut dead, scanned)?;
+
+    Ok(swept)
+}
+
+/// Garbage collects `bs` in-place, deleting every block not reachable from `roots`.
+///
+/// Fails without deleting anything if any reachable block is missing from the store, as the
+/// missing blocks' descendants can't be marked. To collect a damaged store anyway, call [`mark`]
+/// and [`sweep`] directly.
+///
+/// The store must not be written to while collecting: concurrently written blocks won't be marked,
+/// and will be deleted.
+pub fn collect<BS, I, P>(bs: &BS, roots: I, mut progress: P) -> Result<GcStats>
+where
+    BS: Sweepable,
+    I: IntoIterator<Item = Cid>,
+    P: FnMut(GcProgress),
+{
+    let (marked, stats) = mark(bs, roots, &mut progress)?;
+    sweep_marked(bs, &marked, stats, progress)
+}
+
+/// Like [`collect`], but marks reachable blocks in parallel (see [`par_mark`]).
+///
+/// Fails without deleting anything if any reachable block is missing from the store.
+///
+/// The store must not be written to while collecting: concurrently written blocks won't be marked,
+/// and will be deleted.
+pub fn par_collect<BS, I, P>(bs: &BS, roots: I, mut progress: P) -> Result<GcStats>
+where
+    BS: Sweepable + Sync,
+    I: IntoIterator<Item = Cid>,
+    P: FnMut(GcProgress),
+{
+    let (marked, stats) = par_mark(bs, roots, &mut progress)?;
+    sweep_marked(bs, &marked, stats, progress)
+}
+
+/// Copies every block reachable from `roots` from `src` into `dst`, leaving `src` untouched.
+///
+/// The DAG is copied depth-first, and each block is written to `dst` as soon as its children have
+/// been written (the same order the buffered blockstore flushes in). Only the blocks on the
+/// current path are held in memory.
+pub fn collect_into<SRC, DST, I, P>(
+    src: &SRC,
+    dst: &DST,
+    roots: I,
+    mut progress: P,
+) -> Result<GcStats>
+where
+    SRC: Blockstore,
+    DST: Blockstore,
+    I: IntoIterator<Item = Cid>,
+    P: FnMut(GcProgress),
+{
+    let mut copier = Copier {
+        src,
+        dst,
+        seen: HashSet::new(),
+        stats: GcStats::default(),
+        progress: &mut progress,
+    };
+    for root in roots {
+        copier.copy(root)?;
+    }
+
+    let stats = copier.stats;
+    progress(GcProgress::Copy {
+        copied: stats.copied,
+    });
+    Ok(stats)
+}
+
+/// Sweeps everything not in `marked`, unless the mark phase found missing blocks.
+fn sweep_marked<BS, P>(
+    bs: &BS,
+    marked: &HashSet<Cid>,
+    stats: GcStats,
+    progress: P,
+) -> Result<GcStats>
+where
+    BS: Sweepable,
+    P: FnMut(GcProgress),
+{
+    if stats.missing != 0 {
+        bail!(
+            "{} reachable blocks are missing from the store, refusing to sweep",
+            stats.missing
+        );
+    }
+    let swept = sweep(bs, marked, progress)?;
+    Ok(GcStats { swept, ..stats })
+}
+
+/// A block loaded during collection.
+struct Loaded {
+    /// The block's data, if it is stored in the blockstore.
+    block: Option<Vec<u8>>,
+    /// Whether the block should have been in the blockstore, but wasn't.
+    missing: bool,
+    /// The block's direct children.
+    links: Vec<Cid>,
+}
+
+/// A single block visited during the mark phase. The block's data is dropped as soon as it has
+/// been scanned for links.
+struct Visited {
+    /// Whether the block is stored in the blockstore.
+    present: bool,
+    /// Whether the block should have been in the blockstore, but wasn't.
+    missing: bool,
+    /// The block's direct children.
+    links: Vec<Cid>,
+}
+
+/// Walks the DAG under `roots` breadth-first, visiting each level with `visit_level`.
+fn walk<I, V, P>(roots: I, mut visit_level: V, mut progress: P) -> Result<(HashSet<Cid>, GcStats)>
+where
+    I: IntoIterator<Item = Cid>,
+    V: FnMut(&[Cid]) -> Result<Vec<Visited>>,
+    P: FnMut(GcProgress),
+{
+    // Every CID we've queued, including ones that don't correspond to stored blocks.
+    let mut marked = HashSet::new();
+    let mut stats = GcStats::default();
+
+    let mut frontier: Vec<Cid> = roots.into_iter().filter(|c| marked.insert(*c)).collect();
+    while !frontier.is_empty() {
+        let mut next = Vec::new();
+        for v in visit_level(&frontier)? {
+            if v.present {
+                stats.reachable += 1;
+            } else if v.missing {
+                stats.missing += 1;
+            }
+            next.extend(v.links.into_iter().filter(|c| marked.insert(*c)));
+        }
+
+        progress(GcProgress::Mark {
+            reachable: stats.reachable,
+            pending: next.len(),
+        });
+        frontier = next;
+    }
+
+    Ok((marked, stats))
+}
+
+/// Visits a single block during the mark phase.
+fn visit<BS: Blockstore>(bs: &BS, cid: Cid) -> Result<Visited> {
+    // Raw blocks (e.g. wasm bundles) have no links, so there's no need to load them.
+    if cid.codec() == IPLD_RAW && matches!(classify_cid(&cid)?, CidKind::Block) {
+        let present = bs.has(&cid)?;
+        return Ok(Visited {
+            present,
+            missing: !present,
+            links: Vec::new(),
+        });
+    }
+
+    let Loaded {
+        block,
+        missing,
+        links,
+    } = load(bs, cid)?;
+    Ok(Visited {
+        present: block.is_some(),
+        missing,
+        links,
+    })
+}
+
+/// State of a moving collection.
+struct Copier<'a, SRC, DST, P> {
+    src: &'a SRC,
+    dst: &'a DST,
+    /// Every CID we've visited, including ones that don't correspond to stored blocks.
+    seen: HashSet<Cid>,
+    stats: GcStats,
+    progress: P,
+}
+
+impl<SRC, DST, P> Copier<'_, SRC, DST, P>
+where
+    SRC: Blockstore,
+    DST: Blockstore,
+    P: FnMut(GcProgress),
+{
+    /// Copies the DAG under `cid`, writing each block after its children.
+    fn copy(&mut self, cid: Cid) -> Result<()> {
+        if !self.seen.insert(cid) {
+            return Ok(());
+        }
+
+        let Loaded {
+            block,
+            missing,
+            links,
+        } = load(self.src, cid)?;
+        for link in links {
+            self.copy(link)?;
+        }
+
+        if let Some(block) = block {
+            self.dst
+                .put_keyed(&cid, &block)
+                .with_context(|| format!("failed to copy block {cid}"))?;
+            self.stats.reachable += 1;
+            self.stats.copied += 1;
+            if self.stats.copied % COPY_PROGRESS_INTERVAL == 0 {
+                (self.progress)(GcProgress::Copy {
+                    copied: self.stats.copied,
+                });
+            }
+        } else if missing {
+            self.stats.missing += 1;
+        }
+
+        Ok(())
+    }
+}
+
+/// Loads a single block (if it's stored at all) and scans it for links.
+fn load<BS: Blockstore>(bs: &BS, cid: Cid) -> Result<Loaded> {
+    let mut loaded = Loaded {
+        block: None,
+        missing: false,
+        links: Vec::new(),
+    };
+
+    match classify_cid(&cid)? {
+        CidKind::Block => (),
+        // Identity cids have nothing to load, but cbor ones may embed links.
+        CidKind::InlineCbor => {
+            scan_for_links(&mut Cursor::new(cid.hash().digest()), |link| {
+                loaded.links.push(link);
+                Ok(())
+            })?;
+            return Ok(loaded);
+        }
+        CidKind::Ignored => return Ok(loaded),
+    }
+
+    let block = match bs.get(&cid)? {
+        Some(block) => block,
+        None => {
+            loaded.missing = true;
+            return Ok(loaded);
+        }
+    };
+
+    if cid.codec() == DAG_CBOR {
+        scan_for_links(&mut Cursor::new(&block), |link| {
+            loaded.links.push(link);
+            Ok(())
+        })
+        .with_context(|| format!("failed to scan block {cid} for links"))?;
+    }
+    loaded.block = Some(block);
+
+    Ok(loaded)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    use cid::multihash::{Code, Multihash};
+    use fvm_ipld_blockstore::MemoryBlockstore;
+    use fvm_ipld_encoding::CborStore;
+    use fvm_shared::IDENTITY_HASH;
+
+    use super::*;
+
+    /// A threadsafe in-memory blockstore.
+    #[derive(Default)]
+    struct SyncBlockstore(Mutex<HashMap<Cid, Vec<u8>>>);
+
+    impl Blockstore for SyncBlockstore {
+        fn get(&self, k: &Cid) -> Result<Option<Vec<u8>>> {
+            Ok(self.0.lock().unwrap().get(k).cloned())
+        }
+
+        fn put_keyed(&self, k: &Cid, block: &[u8]) -> Result<()> {
+            self.0.lock().unwrap().insert(*k, block.into());
+            Ok(())
+        }
+    }
+
+    impl Sweepable for SyncBlockstore {
+        fn for_each_key<F>(&self, f: F) -> Result<()>
+        where
+            F: FnMut(&Cid) -> Result<()>,
+        {
+            let keys: Vec<Cid> = self.0.lock().unwrap().keys().copied().collect();
+            keys.iter().try_for_each(f)
+        }
+
+        fn delete_many_keyed<I>(&self, keys: I) -> Result<()>
+        where
+            I: IntoIterator<Item = Cid>,
+        {
+            let mut blocks = self.0.lock().unwrap();
+            for k in keys {
+                blocks.remove(&k);
+            }
+            Ok(())
+        }
+    }
+
+    /// A blockstore that refuses to store a block before all of its children.
+    #[derive(Default)]
+    struct ChildrenFirstBlockstore(MemoryBlockstore);
+
+    impl Blockstore for ChildrenFirstBlockstore {
+        fn get(&self, k: &Cid) -> Result<Option<Vec<u8>>> {
+            self.0.get(k)
+        }
+
+        fn put_keyed(&self, k: &Cid, block: &[u8]) -> Result<()> {
+            if k.codec() == DAG_CBOR {
+                scan_for_links(&mut Cursor::new(block), |link| {
+                    if link.hash().code() != IDENTITY_HASH {
+                        assert!(self.0.has(&link)?, "{k} written before its child {link}");
+                    }
+                    Ok(())
+                })?;
+            }
+            self.0.put_keyed(k, block)
+        }
+    }
+
+    /// Builds a small DAG, returning the store, the root, the live blocks, and the dead blocks.
+    fn setup<BS: Blockstore + Default>() -> (BS, Cid, Vec<Cid>, Vec<Cid>) {
+        let bs = BS::default();
+
+        let leaf = bs.put_cbor(&"leaf", Code::Blake2b256).unwrap();
+        let shared = bs.put_cbor(&(leaf, 1u8), Code::Blake2b256).unwrap();
+        let left = bs.put_cbor(&(shared, 2u8), Code::Blake2b256).unwrap();
+        // Links to both a deeper block (shared) and one of its descendants (leaf).
+        let right = bs.put_cbor(&(shared, leaf), Code::Blake2b256).unwrap();
+        let identity = Cid::new_v1(IPLD_RAW, Multihash::wrap(IDENTITY_HASH, &[0]).unwrap());
+        let root = bs
+            .put_cbor(&(left, right, identity, leaf), Code::Blake2b256)
+            .unwrap();
+
+        let orphan_leaf = bs.put_cbor(&"orphan", Code::Blake2b256).unwrap();
+        let orphan = bs.put_cbor(&(orphan_leaf, leaf), Code::Blake2b256).unwrap();
+
+        (
+            bs,
+            root,
+            vec![root, left, right, shared, leaf],
+            vec![orphan, orphan_leaf],
+        )
+    }
+
+    fn check_collected<BS: Blockstore>(bs: &BS, stats: GcStats, live: &[Cid], dead: &[Cid]) {
+        assert_eq!(
+            stats,
+            GcStats {
+                reachable: live.len(),
+                swept: dead.len(),
+                ..Default::default()
+            }
+        );
+        for c in live {
+            assert!(bs.has(c).unwrap());
+        }
+        for c in dead {
+            assert!(!bs.has(c).unwrap());
+        }
+    }
+
+    #[test]
+    fn mark_and_sweep() {
+        let (bs, root, live, dead) = setup::<MemoryBlockstore>();
+
+        let mut reports = Vec::new();
+        let stats = collect(&bs, [root], |p| reports.push(p)).unwrap();
+        check_collected(&bs, stats, &live, &dead);
+
+        // One report per DAG level (root, left/right/identity/leaf, shared), then one for the
+        // sweep.
+        assert_eq!(reports.len(), 4);
+        assert_eq!(
+            reports[2],
+            GcProgress::Mark {
+                reachable: live.len(),
+                pending: 0
+            }
+        );
+        assert_eq!(
+            reports[3],
+            GcProgress::Sweep {
+                scanned: live.len() + dead.len(),
+                swept: dead.len()
+            }
+        );
+    }
+
+    #[test]
+    fn par_mark_and_sweep() {
+        let (bs, root, live, dead) = setup::<SyncBlockstore>();
+        let stats = par_collect(&bs, [root], |_| {}).unwrap();
+        check_collected(&bs, stats, &live, &dead);
+    }
+
+    #[test]
+    fn moving_collection() {
+        let (src, root, live, dead) = setup::<MemoryBlockstore>();
+        let dst = ChildrenFirstBlockstore::default();
+
+        let mut reports = Vec::new();
+        let stats = collect_into(&src, &dst, [root], |p| reports.push(p)).unwrap();
+        assert_eq!(
+            stats,
+            GcStats {
+                reachable: live.len(),
+                copied: live.len(),
+                ..Default::default()
+            }
+        );
+        assert_eq!(reports, [GcProgress::Copy { copied: live.len() }]);
+
+        for c in &live {
+            assert!(dst.has(c).unwrap());
+            assert!(src.has(c).unwrap());
+        }
+        for c in &dead {
+            assert!(!dst.has(c).unwrap());
+            assert!(src.has(c).unwrap());
+        }
+    }
+
+    #[test]
+    fn missing_blocks() {
+        let (bs, root, live, _) = setup::<MemoryBlockstore>();
+        bs.delete_many_keyed([live[3]]).unwrap();
+
+        let (marked, stats) = mark(&bs, [root], |_| {}).unwrap();
+        assert_eq!(stats.missing, 1);
+        assert_eq!(stats.reachable, live.len() - 1);
+        assert!(marked.contains(&live[3]));
+
+        let dst = MemoryBlockstore::new();
+        let stats = collect_into(&bs, &dst, [root], |_| {}).unwrap();
+        assert_eq!(stats.missing, 1);
+        assert_eq!(stats.copied, live.len() - 1);
+    }
+
+    #[test]
+    fn missing_blocks_are_not_swept_past() {
+        let bs = MemoryBlockstore::new();
+        let leaf = bs.put_cbor(&"leaf", Code::Blake2b256).unwrap();
+        let middle = bs.put_cbor(&(leaf,), Code::Blake2b256).unwrap();
+        let root = bs.put_cbor(&(middle,), Code::Blake2b256).unwrap();
+        let orphan = bs.put_cbor(&"orphan", Code::Blake2b256).unwrap();
+        bs.delete_many_keyed([middle]).unwrap();
+
+        // The leaf is only reachable through the missing block, so it can't be marked.
+        assert!(collect(&bs, [root], |_| {}).is_err());
+        for c in [root, leaf, orphan] {
+            assert!(bs.has(&c).unwrap());
+        }
+    }
+
+    #[test]
+    fn sweep_in_batches() {
+        let bs = MemoryBlockstore::new();
+        let root = bs.put_cbor(&"root", Code::Blake2b256).unwrap();
+        for i in 0..=SWEEP_BATCH_SIZE {
+            bs.put_cbor(&i, Code::Blake2b256).unwrap();
+        }
+
+        let mut reports = Vec::new();
+        let stats = collect(&bs, [root], |p| {
+            if let GcProgress::Sweep { swept, .. } = p {
+                reports.push(swept);
+            }
+        })
+        .unwrap();
+        assert_eq!(stats.swept, SWEEP_BATCH_SIZE + 1);
+        assert_eq!(reports, [SWEEP_BATCH_SIZE, SWEEP_BATCH_SIZE + 1]);
+        assert!(bs.has(&root).unwrap());
+    }
+
+    #[test]
+    fn unexpected_cid() {
+        let bs = MemoryBlockstore::new();
+        let sha = bs.put_cbor(&1u8, Code::Sha2_256).unwrap();
+        let root = bs.put_cbor(&(sha,), Code::Blake2b256).unwrap();
+        assert!(collect(&bs, [root], |_| {}).is_err());
+        assert!(bs.has(&root).unwrap());
+    }
+}
@@ -2,3 +2,5 @@
 
 mod buffered;
 pub use buffered::BufferedBlockstore;
+
+pub mod gc;
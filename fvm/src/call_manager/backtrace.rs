@@ -4,6 +4,7 @@ use fvm_shared::address::Address;
 use fvm_shared::error::{ErrorNumber, ExitCode};
 use fvm_shared::{ActorID, MethodNum};
 
+use crate::gas::{Gas, OutOfGasInfo};
 use crate::kernel::SyscallError;
 
 /// A call backtrace records the actors an error was propagated through, from
@@ -61,6 +62,11 @@ impl Backtrace {
         self.cause = Some(cause);
     }
 
+    /// Returns true if the backtrace was caused by running out of gas.
+    pub fn is_out_of_gas(&self) -> bool {
+        matches!(self.cause, Some(Cause::OutOfGas { .. }))
+    }
+
     /// Push a "frame" (actor exit) onto the backtrace.
     ///
     /// This should be called every time an actor exits.
@@ -117,6 +123,21 @@ pub enum Cause {
         /// [environment variables](https://doc.rust-lang.org/std/backtrace/index.html#environment-variables) are enabled.
         backtrace: String,
     },
+    /// The original cause was running out of gas.
+    OutOfGas {
+        /// The name of the gas charge that exceeded the gas limit.
+        charge_name: String,
+        /// The gas used before the failing charge was applied.
+        gas_used: Gas,
+        /// The gas that would have been used had the failing charge succeeded. This is a lower
+        /// bound on the gas needed to execute the message.
+        gas_required: Gas,
+        /// The message's gas limit.
+        gas_limit: Gas,
+        /// The call-stack depth of the innermost actor executing when the charge failed, or 0 if
+        /// the charge was applied outside of any actor.
+        depth: u32,
+    },
 }
 
 impl Cause {
@@ -130,6 +151,17 @@ impl Cause {
         }
     }
 
+    /// Records running out of gas as the cause of a backtrace.
+    pub fn from_out_of_gas(info: &OutOfGasInfo, depth: u32) -> Self {
+        Self::OutOfGas {
+            charge_name: info.charge_name.clone(),
+            gas_used: info.gas_used,
+            gas_required: info.gas_required(),
+            gas_limit: info.gas_limit,
+            depth,
+        }
+    }
+
     /// Records a fatal error as the cause of a backtrace.
     pub fn from_fatal(err: anyhow::Error) -> Self {
         Self::Fatal {
@@ -160,6 +192,19 @@ impl Display for Cause {
             } => {
                 write!(f, "[FATAL] Error: {}, Backtrace:\n{}", error_msg, backtrace)
             }
+            Cause::OutOfGas {
+                charge_name,
+                gas_used,
+                gas_required,
+                gas_limit,
+                depth,
+            } => {
+                write!(
+                    f,
+                    "out of gas -- {} at depth {} required {} gas (used {} of {} before the charge)",
+                    charge_name, depth, gas_required, gas_used, gas_limit,
+                )
+            }
         }
     }
 }
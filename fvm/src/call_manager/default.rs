@@ -9,11 +9,11 @@ use fvm_shared::{ActorID, MethodNum, METHOD_SEND};
 use num_traits::Zero;
 
 use super::{Backtrace, CallManager, InvocationResult, NO_DATA_BLOCK_ID};
-use crate::call_manager::backtrace::Frame;
+use crate::call_manager::backtrace::{Cause, Frame};
 use crate::call_manager::FinishRet;
 use crate::gas::{Gas, GasTracker};
 use crate::kernel::{Block, BlockRegistry, ExecutionError, Kernel, Result, SyscallError};
-use crate::machine::Machine;
+use crate::machine::{InstancePool, Machine};
 use crate::state_tree::ActorState;
 use crate::syscalls::error::Abort;
 use crate::syscalls::{charge_for_exec, update_gas_available};
@@ -48,6 +48,8 @@ pub struct InnerDefaultCallManager<M> {
     exec_trace: ExecutionTrace,
     /// Number of actors that have been invoked in this message execution.
     invocation_count: u64,
+    /// Idle actor instances that can be recycled by subsequent invocations.
+    instance_pool: InstancePool,
 }
 
 #[doc(hidden)]
@@ -93,6 +95,7 @@ where
             backtrace: Backtrace::default(),
             exec_trace: vec![],
             invocation_count: 0,
+            instance_pool: InstancePool::default(),
         })))
     }
 
@@ -183,7 +186,7 @@ where
     fn finish(mut self) -> (FinishRet, Self::Machine) {
         let InnerDefaultCallManager {
             machine,
-            backtrace,
+            mut backtrace,
             mut gas_tracker,
             mut exec_trace,
             ..
@@ -192,6 +195,14 @@ where
         // TODO: Having to check against zero here is fishy, but this is what lotus does.
         let gas_used = gas_tracker.gas_used().max(Gas::zero()).round_up();
 
+        // If we ran out of gas outside of any actor (e.g., while charging for the top-level
+        // invocation), no frame will have recorded it.
+        if !backtrace.is_out_of_gas() {
+            if let Some(info) = gas_tracker.out_of_gas() {
+                backtrace.begin(Cause::from_out_of_gas(info, 0));
+            }
+        }
+
         // Finalize any trace events, if we're tracing.
         if machine.context().tracing {
             exec_trace.extend(gas_tracker.drain_trace().map(ExecutionEvent::GasCharge));
@@ -411,21 +422,45 @@ where
                 |_| syscall_error!(NotFound; "actor code cid does not exist {}", &state.code),
             )?;
 
+        // Reuse an idle instance of the actor's code, if we have one.
+        let recycle_instances = self.machine.context().recycle_instances;
+        let idle = if recycle_instances {
+            self.instance_pool.take::<K>(&state.code)
+        } else {
+            None
+        };
+
         log::trace!("calling {} -> {}::{}", from, to, method);
         self.map_mut(|cm| {
             // Make the kernel.
             let kernel = K::new(cm, block_registry, from, to, method, value.clone());
 
-            // Make a store.
-            let mut store = engine.new_store(kernel);
+            // Make a store, or attach the kernel to the idle instance's store.
+            let (mut store, idle) = match idle {
+                Some(idle) => {
+                    let (store, instance) = idle.attach(kernel);
+                    (store, Some(instance))
+                }
+                None => (engine.new_store(kernel), None),
+            };
+            let mut actor_instance = None;
 
             // From this point on, there are no more syscall errors, only aborts.
             let result: std::result::Result<BlockId, Abort> = (|| {
-                // Instantiate the module.
-                let instance = engine
-                    .get_instance(&mut store, &state.code)
-                    .and_then(|i| i.context("actor code not found"))
-                    .map_err(Abort::Fatal)?;
+                // Instantiate the module, unless we're recycling an instance.
+                let instance = match idle {
+                    Some(instance) => instance,
+                    None if recycle_instances => engine
+                        .get_actor_instance(&mut store, &state.code)
+                        .and_then(|i| i.context("actor code not found"))
+                        .map_err(Abort::Fatal)?,
+                    None => engine
+                        .get_instance(&mut store, &state.code)
+                        .and_then(|i| i.context("actor code not found"))
+                        .map_err(Abort::Fatal)?
+                        .into(),
+                };
+                let instance = actor_instance.insert(instance).instance();
 
                 // Resolve and store a reference to the exported memory.
                 let memory = instance
@@ -459,9 +494,21 @@ where
                 Ok(res?)
             })();
 
-            let invocation_data = store.into_data();
-            let last_error = invocation_data.last_error;
-            let (mut cm, block_registry) = invocation_data.kernel.into_inner();
+            let invocation_data = store.data_mut();
+            let last_error = invocation_data.last_error.take();
+            let (mut cm, block_registry) = invocation_data
+                .kernel
+                .take()
+                .expect("no kernel attached to invocation")
+                .into_inner();
+
+            // Only recycle instances that returned normally, resetting them to their initial
+            // state. Instances that can't be reset are simply dropped.
+            if recycle_instances && result.is_ok() {
+                if let Some(idle) = actor_instance.and_then(|i| i.recycle(store)) {
+                    cm.instance_pool.put(state.code, idle);
+                }
+            }
 
             // Resolve the return block's ID into an actual block, converting to an abort if it
             // doesn't exist.
@@ -482,7 +529,17 @@ where
             let ret = match result {
                 Ok(ret) => Ok(InvocationResult::Return(ret.cloned())),
                 Err(abort) => {
-                    if let Some(err) = last_error {
+                    if matches!(abort, Abort::OutOfGas) {
+                        // Running out of gas isn't caused by the last syscall error (if any).
+                        // Instead, record the charge that exceeded the gas limit, unless a callee
+                        // already did so.
+                        if !cm.backtrace.is_out_of_gas() {
+                            if let Some(info) = cm.gas_tracker.out_of_gas() {
+                                let cause = Cause::from_out_of_gas(info, cm.call_stack_depth);
+                                cm.backtrace.begin(cause);
+                            }
+                        }
+                    } else if let Some(err) = last_error {
                         cm.backtrace.begin(err);
                     }
 
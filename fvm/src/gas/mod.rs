@@ -151,11 +151,34 @@ impl Mul<i32> for Gas {
     }
 }
 
+/// Details of the gas charge that first exceeded the gas limit, recorded to help users estimate
+/// how much gas their message actually needs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OutOfGasInfo {
+    /// The name of the failing gas charge.
+    pub charge_name: String,
+    /// The amount of gas the failing charge attempted to use.
+    pub charge: Gas,
+    /// The gas used before the failing charge was applied.
+    pub gas_used: Gas,
+    /// The gas limit that was exceeded.
+    pub gas_limit: Gas,
+}
+
+impl OutOfGasInfo {
+    /// The total gas that would have been used had the failing charge succeeded. Note that this is
+    /// a lower bound on the gas required to successfully execute the message.
+    pub fn gas_required(&self) -> Gas {
+        self.gas_used + self.charge
+    }
+}
+
 pub struct GasTracker {
     gas_limit: Gas,
     gas_used: Gas,
     gas_premium: TokenAmount,
     trace: Option<Vec<GasCharge>>,
+    out_of_gas: Option<OutOfGasInfo>,
 }
 
 impl GasTracker {
@@ -167,6 +190,7 @@ impl GasTracker {
             gas_used,
             gas_premium,
             trace: None,
+            out_of_gas: None,
         }
     }
 
@@ -176,10 +200,21 @@ impl GasTracker {
 
     fn charge_gas_inner(&mut self, name: &str, to_use: Gas) -> Result<()> {
         log::trace!("charging gas: {} {}", name, to_use);
+        let gas_used = self.gas_used;
         // The gas type uses saturating math.
         self.gas_used += to_use;
         if self.gas_used > self.gas_limit {
             log::trace!("gas limit reached");
+            // Only the first failing charge is interesting, any subsequent charges will fail
+            // because we've already used all available gas.
+            if self.out_of_gas.is_none() {
+                self.out_of_gas = Some(OutOfGasInfo {
+                    charge_name: name.to_owned(),
+                    charge: to_use,
+                    gas_used,
+                    gas_limit: self.gas_limit,
+                });
+            }
             self.gas_used = self.gas_limit;
             Err(ExecutionError::OutOfGas)
         } else {
@@ -226,6 +261,11 @@ impl GasTracker {
         self.gas_premium.clone()
     }
 
+    /// Getter for the details of the charge that exceeded the gas limit, if any.
+    pub fn out_of_gas(&self) -> Option<&OutOfGasInfo> {
+        self.out_of_gas.as_ref()
+    }
+
     pub fn drain_trace(&mut self) -> impl Iterator<Item = GasCharge> + '_ {
         self.trace
             .as_mut()
@@ -267,6 +307,30 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn gas_tracker_out_of_gas_info() -> Result<()> {
+        let mut t = GasTracker::new(Gas::new(20), Gas::zero(), Zero::zero());
+        t.charge_gas("first", Gas::new(15))?;
+        assert_eq!(t.out_of_gas(), None);
+
+        assert!(t.charge_gas("second", Gas::new(10)).is_err());
+        let expected = OutOfGasInfo {
+            charge_name: "second".into(),
+            charge: Gas::new(10),
+            gas_used: Gas::new(15),
+            gas_limit: Gas::new(20),
+        };
+        assert_eq!(t.out_of_gas(), Some(&expected));
+        assert_eq!(expected.gas_required(), Gas::new(25));
+
+        // Subsequent failures don't replace the original charge.
+        assert!(t
+            .apply_charge(GasCharge::new("third", Gas::new(1), Gas::zero()))
+            .is_err());
+        assert_eq!(t.out_of_gas(), Some(&expected));
+        Ok(())
+    }
+
     #[test]
     fn milligas_to_gas_round() {
         assert_eq!(milligas_to_gas(100, false), 0);
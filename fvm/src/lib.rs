@@ -20,6 +20,7 @@ pub mod gas;
 pub mod state_tree;
 
 mod blockstore;
+pub use blockstore::gc;
 
 #[cfg(not(feature = "testing"))]
 mod account_actor;
@@ -51,15 +52,16 @@ lazy_static::lazy_static! {
 
 #[cfg(test)]
 mod test {
-    use fvm_ipld_blockstore::MemoryBlockstore;
+    use fvm_ipld_blockstore::{Blockstore, MemoryBlockstore};
     use fvm_ipld_encoding::CborStore;
+    use fvm_shared::address::Address;
     use fvm_shared::state::StateTreeVersion;
     use multihash::Code;
 
     use crate::call_manager::DefaultCallManager;
     use crate::externs::{Consensus, Externs, Rand};
-    use crate::machine::{DefaultMachine, Engine, Manifest, NetworkConfig};
-    use crate::state_tree::StateTree;
+    use crate::machine::{DefaultMachine, Engine, Machine, Manifest, NetworkConfig};
+    use crate::state_tree::{ActorState, StateTree};
     use crate::{executor, DefaultKernel};
 
     struct DummyExterns;
@@ -131,4 +133,79 @@ mod test {
             machine,
         ));
     }
+
+    #[test]
+    fn test_state_tree_at() {
+        let mut bs = MemoryBlockstore::default();
+        let mut st = StateTree::new(bs, StateTreeVersion::V4).unwrap();
+        let actor = ActorState::new(
+            *crate::EMPTY_ARR_CID,
+            *crate::EMPTY_ARR_CID,
+            Default::default(),
+            0,
+            None,
+        );
+        st.set_actor_id(100, actor).unwrap();
+        let old_root = st.flush().unwrap();
+        bs = st.into_store();
+
+        // An empty built-in actors manifest.
+        let manifest_cid = {
+            bs.put_cbor(&Manifest::DUMMY_CODES, Code::Blake2b256)
+                .unwrap()
+        };
+
+        let actors_cid = bs.put_cbor(&(1, manifest_cid), Code::Blake2b256).unwrap();
+
+        let mc = NetworkConfig::new(fvm_shared::version::NetworkVersion::V18)
+            .override_actors(actors_cid)
+            .for_epoch(0, old_root);
+
+        let mut machine = DefaultMachine::new(
+            &Engine::new_default((&mc.network).into()).unwrap(),
+            &mc,
+            bs,
+            DummyExterns,
+        )
+        .unwrap();
+
+        // Update the actor and compute a new root, leaving the new blocks in the machine's write
+        // buffer.
+        machine
+            .state_tree_mut()
+            .mutate_actor_id(100, |actor| {
+                actor.sequence = 1;
+                Ok(())
+            })
+            .unwrap();
+        let new_root = machine.state_tree_mut().flush().unwrap();
+
+        let old = machine.state_tree_at(&old_root).unwrap();
+        assert_eq!(old.root(), &old_root);
+        assert_eq!(old.get_actor_id(100).unwrap().unwrap().sequence, 0);
+        assert_eq!(old.cache().cached_actors(), 1);
+
+        // Re-opening a view at the same root shares the cache.
+        let again = machine.state_tree_at(&old_root).unwrap();
+        assert_eq!(again.cache().cached_actors(), 1);
+        assert_eq!(again.get_actor_id(100).unwrap().unwrap().sequence, 0);
+        assert_eq!(again.cache().cached_actors(), 1);
+
+        // Views at other roots are cached separately.
+        let new = machine.state_tree_at(&new_root).unwrap();
+        assert_eq!(
+            new.get_actor(&Address::new_id(100))
+                .unwrap()
+                .unwrap()
+                .sequence,
+            1
+        );
+        assert_eq!(new.cache().cached_actors(), 2);
+        drop((old, again, new));
+
+        // The new root was only ever visible through the machine's buffered blockstore.
+        let base = machine.into_store().into_inner();
+        assert!(base.has(&old_root).unwrap());
+        assert!(!base.has(&new_root).unwrap());
+    }
 }
@@ -5,7 +5,7 @@ use fvm_shared::ActorID;
 
 use super::{Engine, Machine, MachineContext, Manifest};
 use crate::kernel::Result;
-use crate::state_tree::{ActorState, StateTree};
+use crate::state_tree::{ActorState, StateTree, StateTreeView};
 
 type Type = MachineContext;
 
@@ -58,6 +58,11 @@ impl<M: Machine> Machine for Box<M> {
         (**self).transfer(from, to, value)
     }
 
+    #[inline(always)]
+    fn state_tree_at(&self, root: &Cid) -> Result<StateTreeView<&Self::Blockstore>> {
+        (**self).state_tree_at(root)
+    }
+
     #[inline(always)]
     fn into_store(self) -> Self::Blockstore {
         (*self).into_store()
@@ -1,4 +1,5 @@
 use std::ops::RangeInclusive;
+use std::sync::Arc;
 
 use anyhow::{anyhow, Context as _};
 use cid::Cid;
@@ -18,7 +19,7 @@ use crate::externs::Externs;
 use crate::init_actor::State as InitActorState;
 use crate::kernel::{ClassifyResult, Context as _, Result};
 use crate::machine::Manifest;
-use crate::state_tree::{ActorState, StateTree};
+use crate::state_tree::{ActorState, StateTree, StateTreeView, StateTreeViewCache};
 use crate::syscall_error;
 use crate::system_actor::State as SystemActorState;
 
@@ -36,6 +37,9 @@ pub struct DefaultMachine<B, E> {
     ///
     /// Owned.
     state_tree: StateTree<BufferedBlockstore<B>>,
+    /// Actor lookups shared by all the state tree views handed out by
+    /// [`Machine::state_tree_at`].
+    state_views: Arc<StateTreeViewCache>,
     /// Mapping of CIDs to builtin actor types.
     builtin_actors: Manifest,
     /// Somewhat unique ID of the machine consisting of (epoch, randomness)
@@ -147,6 +151,7 @@ where
             engine: engine.clone(),
             externs,
             state_tree,
+            state_views: Default::default(),
             builtin_actors,
             id: format!(
                 "{}-{}",
@@ -204,6 +209,10 @@ where
         Ok(root)
     }
 
+    fn state_tree_at(&self, root: &Cid) -> Result<StateTreeView<&Self::Blockstore>> {
+        StateTreeView::with_cache(self.blockstore(), root, self.state_views.clone())
+    }
+
     /// Creates an uninitialized actor.
     fn create_actor(&mut self, addr: &Address, act: ActorState) -> Result<ActorID> {
         let state_tree = self.state_tree_mut();
@@ -2,6 +2,8 @@ use std::any::{Any, TypeId};
 use std::collections::hash_map::Entry::{Occupied, Vacant};
 use std::collections::HashMap;
 use std::ops::Deref;
+#[cfg(feature = "testing")]
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 
 use anyhow::{anyhow, Context};
@@ -10,7 +12,10 @@ use fvm_ipld_blockstore::Blockstore;
 use fvm_wasm_instrument::gas_metering::GAS_COUNTER_NAME;
 use fvm_wasm_instrument::parity_wasm::elements;
 use wasmtime::OptLevel::Speed;
-use wasmtime::{Global, GlobalType, Linker, Memory, MemoryType, Module, Mutability, Val, ValType};
+use wasmtime::{
+    Global, GlobalType, Instance, Linker, Memory, MemoryType, Module, Mutability, Store, Val,
+    ValType,
+};
 
 use super::Machine;
 use crate::gas::WasmGasPrices;
@@ -18,6 +23,16 @@ use crate::machine::NetworkConfig;
 use crate::syscalls::{bind_syscalls, InvocationData};
 use crate::Kernel;
 
+/// Prefix of the exports added to recyclable modules for each of their mutable globals, so that
+/// the globals can be reset between invocations.
+const RESET_GLOBAL_EXPORT_PREFIX: &str = "__fvm_reset_global_";
+
+/// Export (of the module's memory) marking a module as recyclable.
+const RECYCLABLE_EXPORT: &str = "__fvm_recyclable";
+
+/// Granularity (in bytes) at which memory is compared and restored when resetting an instance.
+const RESET_CHUNK_SIZE: usize = 4096;
+
 /// A caching wasmtime engine.
 #[derive(Clone)]
 pub struct Engine(Arc<EngineInner>);
@@ -155,6 +170,15 @@ struct EngineInner {
     dummy_memory: Memory,
 
     module_cache: Mutex<HashMap<Cid, Module>>,
+    /// Modules prepared for instance recycling, loaded on demand when recycling is enabled. `None`
+    /// for modules that can't be recycled, which are instantiated from `module_cache` instead.
+    recyclable_module_cache: Mutex<HashMap<Cid, Option<Module>>>,
+    /// The initial state of each recyclable module's instances, captured from the module's first
+    /// instance.
+    instance_images: Mutex<HashMap<Cid, Option<Arc<InstanceImage>>>>,
+    /// Number of instances created (recycled instances aren't counted).
+    #[cfg(feature = "testing")]
+    instantiations: AtomicU64,
     instance_cache: Mutex<HashMap<TypeId, Box<dyn Any + Send>>>,
     config: EngineConfig,
 
@@ -193,6 +217,10 @@ impl Engine {
             dummy_memory,
             dummy_gas_global: dummy_gg,
             module_cache: Default::default(),
+            recyclable_module_cache: Default::default(),
+            instance_images: Default::default(),
+            #[cfg(feature = "testing")]
+            instantiations: Default::default(),
             instance_cache: Mutex::new(HashMap::new()),
             config: ec,
             actor_redirect,
@@ -276,6 +304,28 @@ impl Engine {
     }
 
     fn load_raw(&self, raw_wasm: &[u8]) -> anyhow::Result<Module> {
+        let m = self.instrument(raw_wasm)?;
+        let wasm = m.to_bytes()?;
+        let module = Module::from_binary(&self.0.engine, wasm.as_slice())?;
+
+        Ok(module)
+    }
+
+    /// Like [`Engine::load_raw`], but exports the module's state so its instances can be
+    /// recycled. Returns `None` if the module can't be recycled.
+    fn load_recyclable(&self, raw_wasm: &[u8]) -> anyhow::Result<Option<Module>> {
+        let mut m = self.instrument(raw_wasm)?;
+        if !mark_recyclable(&mut m) {
+            return Ok(None);
+        }
+        let wasm = m.to_bytes()?;
+        let module = Module::from_binary(&self.0.engine, wasm.as_slice())?;
+
+        Ok(Some(module))
+    }
+
+    /// Validates and instruments an actor's wasm code.
+    fn instrument(&self, raw_wasm: &[u8]) -> anyhow::Result<elements::Module> {
         // First make sure that non-instrumented wasm is valid
         Module::validate(&self.0.engine, raw_wasm)
             .map_err(anyhow::Error::msg)
@@ -310,10 +360,7 @@ impl Engine {
         // Work around #602. Remove this once paritytech/parity-wasm#331 is merged and bubbled.
         fix_wasm_sections(&mut m);
 
-        let wasm = m.to_bytes()?;
-        let module = Module::from_binary(&self.0.engine, wasm.as_slice())?;
-
-        Ok(module)
+        Ok(m)
     }
 
     /// Load compiled wasm code into the engine.
@@ -365,7 +412,76 @@ impl Engine {
         store: &mut wasmtime::Store<InvocationData<K>>,
         k: &Cid,
     ) -> anyhow::Result<Option<wasmtime::Instance>> {
-        let k = self.with_redirect(k);
+        let module = match self.get_module(store.data().kernel().machine().blockstore(), k)? {
+            Some(module) => module,
+            None => return Ok(None),
+        };
+        self.instantiate(store, &module).map(Some)
+    }
+
+    /// Like [`Engine::get_instance`], but prepares the instance to be reset and recycled (if the
+    /// module supports it).
+    ///
+    /// Recyclable modules are loaded separately from the regular module cache. The initial state
+    /// is captured once per module, from its first instance, and shared by all later instances.
+    pub(crate) fn get_actor_instance<K: Kernel>(
+        &self,
+        store: &mut wasmtime::Store<InvocationData<K>>,
+        k: &Cid,
+    ) -> anyhow::Result<Option<ActorInstance>> {
+        let code = self.with_redirect(k);
+        let blockstore = store.data().kernel().machine().blockstore();
+        let module = match self.get_recyclable_module(blockstore, code)? {
+            Some(module) => module,
+            // Either the module can't be recycled, or it doesn't exist.
+            None => return Ok(self.get_instance(store, k)?.map(ActorInstance::from)),
+        };
+        let instance = self.instantiate(store, &module)?;
+        let image = self
+            .0
+            .instance_images
+            .lock()
+            .expect("instance_images poisoned")
+            .entry(*code)
+            .or_insert_with(|| InstanceImage::capture(&mut *store, &instance).map(Arc::new))
+            .clone();
+        Ok(Some(ActorInstance {
+            reset: image.and_then(|image| InstanceReset::new(store, &instance, image)),
+            instance,
+        }))
+    }
+
+    /// Lookup a module prepared for instance recycling, loading it from the blockstore if
+    /// necessary. Returns `None` if the module can't be recycled, or doesn't exist.
+    fn get_recyclable_module(
+        &self,
+        blockstore: &impl Blockstore,
+        k: &Cid,
+    ) -> anyhow::Result<Option<Module>> {
+        match self
+            .0
+            .recyclable_module_cache
+            .lock()
+            .expect("recyclable_module_cache poisoned")
+            .entry(*k)
+        {
+            Occupied(v) => Ok(v.get().clone()),
+            Vacant(v) => match blockstore
+                .get(k)
+                .context("failed to lookup wasm module in blockstore")?
+            {
+                Some(raw_wasm) => Ok(v.insert(self.load_recyclable(&raw_wasm)?).clone()),
+                None => Ok(None),
+            },
+        }
+    }
+
+    /// Instantiates a module with the given store, using the (cached) linker for the kernel.
+    fn instantiate<K: Kernel>(
+        &self,
+        store: &mut wasmtime::Store<InvocationData<K>>,
+        module: &Module,
+    ) -> anyhow::Result<wasmtime::Instance> {
         let mut instance_cache = self.0.instance_cache.lock().expect("cache poisoned");
 
         let type_id = TypeId::of::<K>();
@@ -389,31 +505,23 @@ impl Engine {
             .linker
             .define("gas", GAS_COUNTER_NAME, store.data_mut().avail_gas_global)?;
 
-        let mut module_cache = self.0.module_cache.lock().expect("module_cache poisoned");
-        match module_cache.entry(*k) {
-            Occupied(v) => Ok(Some(cache.linker.instantiate(&mut *store, v.get())?)),
-            Vacant(v) => match store
-                .data()
-                .kernel
-                .machine()
-                .blockstore()
-                .get(k)
-                .context("failed to lookup wasm module in blockstore")?
-            {
-                Some(raw_wasm) => Ok(Some(
-                    cache
-                        .linker
-                        .instantiate(&mut *store, v.insert(self.load_raw(&raw_wasm)?))?,
-                )),
-                None => Ok(None),
-            },
-        }
+        let instance = cache.linker.instantiate(&mut *store, module)?;
+        #[cfg(feature = "testing")]
+        self.0.instantiations.fetch_add(1, Ordering::Relaxed);
+        Ok(instance)
+    }
+
+    /// Returns the number of instances this engine has created. Recycled instances aren't
+    /// counted.
+    #[cfg(feature = "testing")]
+    pub fn instantiation_count(&self) -> u64 {
+        self.0.instantiations.load(Ordering::Relaxed)
     }
 
     /// Construct a new wasmtime "store" from the given kernel.
     pub fn new_store<K: Kernel>(&self, kernel: K) -> wasmtime::Store<InvocationData<K>> {
         let id = InvocationData {
-            kernel,
+            kernel: Some(kernel),
             last_error: None,
             avail_gas_global: self.0.dummy_gas_global,
             last_milligas_available: 0,
@@ -430,6 +538,290 @@ impl Engine {
     }
 }
 
+/// An actor's wasm instance, along with the means to reset it if it can be recycled.
+pub(crate) struct ActorInstance {
+    instance: Instance,
+    reset: Option<InstanceReset>,
+}
+
+impl From<Instance> for ActorInstance {
+    /// Wraps an instance that won't be recycled.
+    fn from(instance: Instance) -> Self {
+        ActorInstance {
+            instance,
+            reset: None,
+        }
+    }
+}
+
+impl ActorInstance {
+    /// Returns the underlying wasmtime instance.
+    pub fn instance(&self) -> Instance {
+        self.instance
+    }
+
+    /// Resets the instance to its initial state so it can be reused by a later invocation of the
+    /// same code, consuming the store that owns it. The kernel must already have been detached
+    /// from the store.
+    ///
+    /// Returns `None` if the instance isn't recyclable, or if it couldn't be reset.
+    pub fn recycle<K>(self, mut store: Store<InvocationData<K>>) -> Option<IdleInstance<K>> {
+        debug_assert!(
+            store.data().kernel.is_none(),
+            "recycling an attached instance"
+        );
+        if !self.reset.as_ref()?.reset(&mut store) {
+            return None;
+        }
+        Some(IdleInstance {
+            store,
+            instance: self,
+        })
+    }
+}
+
+/// A recycled actor instance, reset to its initial state, along with the store that owns it.
+pub(crate) struct IdleInstance<K> {
+    store: Store<InvocationData<K>>,
+    instance: ActorInstance,
+}
+
+impl<K> IdleInstance<K> {
+    /// Attaches a kernel to the instance's store, readying it for a new invocation.
+    pub fn attach(self, kernel: K) -> (Store<InvocationData<K>>, ActorInstance) {
+        let IdleInstance {
+            mut store,
+            instance,
+        } = self;
+        let data = store.data_mut();
+        data.kernel = Some(kernel);
+        data.last_error = None;
+        (store, instance)
+    }
+}
+
+/// Idle actor instances available for recycling within a single call stack, keyed by kernel type
+/// and code CID.
+///
+/// Only instances whose invocation has returned end up in the pool, so only calls made one after
+/// the other benefit. A chain of nested calls into the same code (e.g., A -> A -> A) still
+/// instantiates the module once per frame, as every instance in the chain is still executing.
+#[derive(Default)]
+pub(crate) struct InstancePool(HashMap<(TypeId, Cid), Vec<Box<dyn Any>>>);
+
+impl InstancePool {
+    /// Takes an idle instance of the given code, if any.
+    pub fn take<K: Kernel>(&mut self, code: &Cid) -> Option<IdleInstance<K>> {
+        let idle = self.0.get_mut(&(TypeId::of::<K>(), *code))?.pop()?;
+        Some(
+            *idle
+                .downcast::<IdleInstance<K>>()
+                .expect("invalid instance pool entry"),
+        )
+    }
+
+    /// Returns an idle instance of the given code to the pool.
+    pub fn put<K: Kernel>(&mut self, code: Cid, idle: IdleInstance<K>) {
+        self.0
+            .entry((TypeId::of::<K>(), code))
+            .or_default()
+            .push(Box::new(idle));
+    }
+}
+
+/// The initial state of a recyclable module's instances.
+struct InstanceImage {
+    /// The initial contents of the instance's memory.
+    memory: Vec<u8>,
+    /// The export names of the instance's mutable globals, along with their initial values.
+    globals: Vec<(String, Val)>,
+}
+
+impl InstanceImage {
+    /// Captures the state of a freshly created instance. Returns `None` if the instance's module
+    /// wasn't marked as recyclable when it was loaded.
+    fn capture<T>(store: &mut Store<T>, instance: &Instance) -> Option<Self> {
+        let memory = instance.get_memory(&mut *store, RECYCLABLE_EXPORT)?;
+        let names: Vec<String> = instance
+            .exports(&mut *store)
+            .map(|e| e.name())
+            .filter(|name| name.starts_with(RESET_GLOBAL_EXPORT_PREFIX))
+            .map(String::from)
+            .collect();
+        let globals = names
+            .into_iter()
+            .map(|name| {
+                let val = instance.get_global(&mut *store, &name)?.get(&mut *store);
+                Some((name, val))
+            })
+            .collect::<Option<_>>()?;
+        Some(Self {
+            memory: memory.data(&*store).to_vec(),
+            globals,
+        })
+    }
+}
+
+/// Handles to a recyclable instance's state, used to reset it to its module's initial state.
+struct InstanceReset {
+    image: Arc<InstanceImage>,
+    memory: Memory,
+    /// The instance's mutable globals, in the same order as the image's.
+    globals: Vec<Global>,
+}
+
+impl InstanceReset {
+    fn new<T>(
+        store: &mut Store<T>,
+        instance: &Instance,
+        image: Arc<InstanceImage>,
+    ) -> Option<Self> {
+        let memory = instance.get_memory(&mut *store, RECYCLABLE_EXPORT)?;
+        let globals = image
+            .globals
+            .iter()
+            .map(|(name, _)| instance.get_global(&mut *store, name))
+            .collect::<Option<_>>()?;
+        Some(Self {
+            image,
+            memory,
+            globals,
+        })
+    }
+
+    /// Restores the instance's initial state, then verifies that the instance matches it. Returns
+    /// false if the instance couldn't be reset.
+    ///
+    /// Memory is compared chunk by chunk, and only the chunks that differ are restored.
+    fn reset<T>(&self, store: &mut Store<T>) -> bool {
+        // Memories can't shrink, so there's no way to reset an instance whose memory has grown.
+        if self.memory.data_size(&*store) != self.image.memory.len() {
+            return false;
+        }
+        let chunks = self
+            .memory
+            .data_mut(&mut *store)
+            .chunks_mut(RESET_CHUNK_SIZE)
+            .zip(self.image.memory.chunks(RESET_CHUNK_SIZE));
+        for (chunk, initial) in chunks {
+            if chunk != initial {
+                chunk.copy_from_slice(initial);
+            }
+        }
+        for (global, (_, val)) in self.globals.iter().zip(&self.image.globals) {
+            if global.set(&mut *store, val.clone()).is_err() {
+                return false;
+            }
+        }
+        self.verify(store)
+    }
+
+    /// Checks that the instance's entire state (memory size and contents, and globals) matches
+    /// the initial state, independently of what [`InstanceReset::reset`] restored.
+    fn verify<T>(&self, store: &mut Store<T>) -> bool {
+        self.memory.data(&*store) == self.image.memory.as_slice()
+            && self
+                .globals
+                .iter()
+                .zip(&self.image.globals)
+                .all(|(global, (_, val))| val_eq(&global.get(&mut *store), val))
+    }
+}
+
+/// Compares wasm values by their bits.
+fn val_eq(a: &Val, b: &Val) -> bool {
+    match (a, b) {
+        (Val::I32(a), Val::I32(b)) => a == b,
+        (Val::I64(a), Val::I64(b)) => a == b,
+        (Val::F32(a), Val::F32(b)) => a == b,
+        (Val::F64(a), Val::F64(b)) => a == b,
+        _ => false,
+    }
+}
+
+/// Marks a module as recyclable by exporting all of its mutable globals, along with its memory
+/// under [`RECYCLABLE_EXPORT`]. Returns false if the module can't be recycled.
+///
+/// Modules with state we can't snapshot and reset through exports are left untouched. That is,
+/// modules that import their memory, have passive data/element segments, or mutate their tables.
+/// So are modules with a start function, as their initial state could depend on the kernel they
+/// were first instantiated with.
+fn mark_recyclable(module: &mut elements::Module) -> bool {
+    use elements::{BulkInstruction, ExportEntry, ImportCountType, Instruction, Internal};
+
+    let defines_memory = module
+        .memory_section()
+        .map(|s| s.entries().len() == 1)
+        .unwrap_or(false);
+    let has_passive_segments = module
+        .data_section()
+        .map(|s| s.entries().iter().any(|d| d.passive()))
+        .unwrap_or(false)
+        || module
+            .elements_section()
+            .map(|s| s.entries().iter().any(|e| e.passive()))
+            .unwrap_or(false);
+    let mutates_tables = module
+        .code_section()
+        .map(|s| {
+            s.bodies().iter().any(|b| {
+                b.code().elements().iter().any(|i| {
+                    matches!(
+                        i,
+                        Instruction::Bulk(
+                            BulkInstruction::TableCopy
+                                | BulkInstruction::TableInit(_)
+                                | BulkInstruction::TableDrop(_)
+                        )
+                    )
+                })
+            })
+        })
+        .unwrap_or(false);
+    if module.import_count(ImportCountType::Memory) > 0
+        || module.start_section().is_some()
+        || !defines_memory
+        || has_passive_segments
+        || mutates_tables
+    {
+        return false;
+    }
+
+    // Imported globals come first in the index space.
+    let imported_globals = module.import_count(ImportCountType::Global) as u32;
+    let mutable_globals: Vec<u32> = module
+        .global_section()
+        .map(|s| {
+            s.entries()
+                .iter()
+                .enumerate()
+                .filter(|(_, g)| g.global_type().is_mutable())
+                .map(|(i, _)| imported_globals + i as u32)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let exports = match module.export_section_mut() {
+        Some(s) => s.entries_mut(),
+        None => return false,
+    };
+    // Don't clobber the module's own exports.
+    if exports.iter().any(|e| e.field().starts_with("__fvm_")) {
+        return false;
+    }
+    exports.extend(mutable_globals.into_iter().map(|idx| {
+        ExportEntry::new(
+            format!("{RESET_GLOBAL_EXPORT_PREFIX}{idx}"),
+            Internal::Global(idx),
+        )
+    }));
+    exports.push(ExportEntry::new(
+        RECYCLABLE_EXPORT.to_owned(),
+        Internal::Memory(0),
+    ));
+    true
+}
+
 // Workaround for https://github.com/filecoin-project/ref-fvm/issues/602
 //
 // This removes the out-of-order data count section, if it exists, and re-inserts it (with the
@@ -444,3 +836,175 @@ fn fix_wasm_sections(module: &mut elements::Module) {
             .expect("section wasn't deleted");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use fvm_shared::version::NetworkVersion;
+    use fvm_shared::IPLD_RAW;
+    use multihash::{Code, MultihashDigest};
+    use wasmtime::TypedFunc;
+
+    use super::*;
+    use crate::gas::price_list_by_network_version;
+
+    /// Returns the sum of its global and the first byte of its memory, then dirties both.
+    const DIRTY_WAT: &str = r#"(module
+        (memory (export "memory") 1)
+        (global $g (mut i32) (i32.const 0))
+        (data (i32.const 0) "\01")
+        (func (export "invoke") (param $x i32) (result i32)
+          (local $ret i32)
+          (local.set $ret (i32.add (global.get $g) (i32.load8_u (i32.const 0))))
+          (global.set $g (i32.add (global.get $g) (i32.const 10)))
+          (i32.store8 (i32.const 0) (i32.const 100))
+          (i32.store8 (i32.const 1000) (i32.const 7))
+          (local.get $ret)))"#;
+
+    fn engine() -> Engine {
+        Engine::new_default(EngineConfig {
+            max_wasm_stack: 2048,
+            wasm_prices: &price_list_by_network_version(NetworkVersion::V18).wasm_rules,
+            actor_redirect: vec![],
+        })
+        .unwrap()
+    }
+
+    fn wat2wasm(wat: &str) -> Vec<u8> {
+        let mut features = wabt::Features::new();
+        features.enable_bulk_memory();
+        wabt::wat2wasm_with_features(wat, features).unwrap()
+    }
+
+    /// Loads the given wat into the engine, prepared for recycling if possible.
+    fn load(engine: &Engine, wat: &str) -> Module {
+        let wasm = wat2wasm(wat);
+        engine
+            .load_recyclable(&wasm)
+            .unwrap()
+            .unwrap_or_else(|| engine.load_raw(&wasm).unwrap())
+    }
+
+    /// Instantiates the module in a fresh store.
+    fn instantiate(
+        engine: &Engine,
+        module: &Module,
+    ) -> (Store<()>, Instance, TypedFunc<(u32,), u32>) {
+        let mut store = Store::new(engine, ());
+        let gas = Global::new(
+            &mut store,
+            GlobalType::new(ValType::I64, Mutability::Var),
+            Val::I64(i64::MAX),
+        )
+        .unwrap();
+        let mut linker = Linker::new(engine);
+        linker.define("gas", GAS_COUNTER_NAME, gas).unwrap();
+        let instance = linker.instantiate(&mut store, module).unwrap();
+        let invoke = instance.get_typed_func(&mut store, "invoke").unwrap();
+        (store, instance, invoke)
+    }
+
+    /// Instantiates the given wat, capturing the fresh instance's image (if it's recyclable).
+    fn instantiate_wat(
+        engine: &Engine,
+        wat: &str,
+    ) -> (Store<()>, TypedFunc<(u32,), u32>, Option<InstanceReset>) {
+        let module = load(engine, wat);
+        let (mut store, instance, invoke) = instantiate(engine, &module);
+        let reset = InstanceImage::capture(&mut store, &instance)
+            .and_then(|image| InstanceReset::new(&mut store, &instance, Arc::new(image)));
+        (store, invoke, reset)
+    }
+
+    #[test]
+    fn reset_instance() {
+        let engine = engine();
+        let (mut store, invoke, reset) = instantiate_wat(&engine, DIRTY_WAT);
+        let reset = reset.expect("module should be recyclable");
+        assert!(reset.verify(&mut store));
+
+        assert_eq!(invoke.call(&mut store, (0,)).unwrap(), 1);
+        assert!(!reset.verify(&mut store));
+        // Without a reset, state leaks between invocations.
+        assert_eq!(invoke.call(&mut store, (0,)).unwrap(), 110);
+
+        assert!(reset.reset(&mut store));
+        assert!(reset.verify(&mut store));
+        assert_eq!(reset.memory.data(&store)[1000], 0);
+        assert_eq!(invoke.call(&mut store, (0,)).unwrap(), 1);
+    }
+
+    #[test]
+    fn regular_modules_are_not_recyclable() {
+        let engine = engine();
+        let wasm = wat2wasm(DIRTY_WAT);
+        let k = Cid::new_v1(IPLD_RAW, Code::Blake2b256.digest(&wasm));
+        let module = engine.prepare_wasm_bytecode(&k, &wasm).unwrap();
+        let (mut store, instance, _) = instantiate(&engine, &module);
+        assert!(InstanceImage::capture(&mut store, &instance).is_none());
+    }
+
+    #[test]
+    fn image_is_shared_between_instances() {
+        let engine = engine();
+        let module = load(&engine, DIRTY_WAT);
+
+        let (mut first, instance, invoke) = instantiate(&engine, &module);
+        let image = Arc::new(InstanceImage::capture(&mut first, &instance).unwrap());
+        assert_eq!(invoke.call(&mut first, (0,)).unwrap(), 1);
+
+        // The image reflects the initial state, not that of the instance it was captured from.
+        let (mut second, instance, invoke) = instantiate(&engine, &module);
+        let reset = InstanceReset::new(&mut second, &instance, image).unwrap();
+        assert!(reset.verify(&mut second));
+        assert_eq!(invoke.call(&mut second, (0,)).unwrap(), 1);
+        assert!(reset.reset(&mut second));
+        assert_eq!(invoke.call(&mut second, (0,)).unwrap(), 1);
+    }
+
+    #[test]
+    fn grown_memory_is_not_reset() {
+        let engine = engine();
+        let (mut store, invoke, reset) = instantiate_wat(
+            &engine,
+            r#"(module
+                 (memory (export "memory") 1)
+                 (func (export "invoke") (param $x i32) (result i32)
+                   (memory.grow (i32.const 1))))"#,
+        );
+        let reset = reset.expect("module should be recyclable");
+        assert_eq!(invoke.call(&mut store, (0,)).unwrap(), 1);
+        assert!(!reset.reset(&mut store));
+    }
+
+    #[test]
+    fn passive_segments_are_not_recyclable() {
+        let engine = engine();
+        let (_, _, reset) = instantiate_wat(
+            &engine,
+            r#"(module
+                 (memory (export "memory") 1)
+                 (data "passive")
+                 (func (export "invoke") (param $x i32) (result i32)
+                   (memory.init 0 (i32.const 0) (i32.const 0) (i32.const 7))
+                   (data.drop 0)
+                   (i32.const 0)))"#,
+        );
+        assert!(reset.is_none());
+    }
+
+    #[test]
+    fn start_function_is_not_recyclable() {
+        let engine = engine();
+        let (_, _, reset) = instantiate_wat(
+            &engine,
+            r#"(module
+                 (memory (export "memory") 1)
+                 (func $start
+                   (i32.store8 (i32.const 0) (i32.const 1)))
+                 (start $start)
+                 (func (export "invoke") (param $x i32) (result i32)
+                   (i32.const 0)))"#,
+        );
+        assert!(reset.is_none());
+    }
+}
@@ -11,7 +11,7 @@ use num_traits::Zero;
 use crate::externs::Externs;
 use crate::gas::{price_list_by_network_version, PriceList};
 use crate::kernel::Result;
-use crate::state_tree::{ActorState, StateTree};
+use crate::state_tree::{ActorState, StateTree, StateTreeView};
 
 mod default;
 
@@ -23,6 +23,7 @@ pub use manifest::Manifest;
 
 mod engine;
 
+pub(crate) use engine::InstancePool;
 pub use engine::{Engine, EngineConfig, MultiEngine};
 
 mod boxed;
@@ -84,6 +85,20 @@ pub trait Machine: 'static {
         self.state_tree_mut().flush()
     }
 
+    /// Opens a read-only view of the state tree at the given state root (e.g., a historical
+    /// root for lookback queries or reorg handling).
+    ///
+    /// The view reads through the machine's blockstore, so it can see blocks written but not yet
+    /// flushed to the underlying store. It does not observe or affect the machine's current state
+    /// tree.
+    ///
+    /// By default, each view has its own cache. Implementations should share a
+    /// [`StateTreeViewCache`](crate::state_tree::StateTreeViewCache) between all the views they
+    /// hand out, as [`DefaultMachine`] does.
+    fn state_tree_at(&self, root: &Cid) -> Result<StateTreeView<&Self::Blockstore>> {
+        StateTreeView::new(self.blockstore(), root)
+    }
+
     /// Consumes the machine and returns the owned blockstore.
     fn into_store(self) -> Self::Blockstore;
 
@@ -175,6 +190,7 @@ impl NetworkConfig {
             initial_state_root: initial_state,
             circ_supply: fvm_shared::TOTAL_FILECOIN.clone(),
             tracing: false,
+            recycle_instances: true,
         }
     }
 
@@ -190,6 +206,7 @@ impl NetworkConfig {
             initial_state_root: initial_state,
             circ_supply: fvm_shared::TOTAL_FILECOIN.clone(),
             tracing: false,
+            recycle_instances: true,
         }
     }
 }
@@ -235,6 +252,13 @@ pub struct MachineContext {
     /// Whether or not to produce execution traces in the returned result.
     /// Not consensus-critical, but has a performance impact.
     pub tracing: bool,
+
+    /// Whether or not to recycle actor instances between invocations of the same code within a
+    /// message. Not consensus-critical, but has a performance impact. When disabled, modules are
+    /// loaded and instantiated without any of the preparation recycling requires.
+    ///
+    /// DEFAULT: `true`
+    pub recycle_instances: bool,
 }
 
 impl MachineContext {
@@ -255,4 +279,10 @@ impl MachineContext {
         self.tracing = true;
         self
     }
+
+    /// Disable instance recycling. [`MachineContext::recycle_instances`].
+    pub fn disable_instance_recycling(&mut self) -> &mut Self {
+        self.recycle_instances = false;
+        self
+    }
 }
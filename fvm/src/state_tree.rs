@@ -3,6 +3,7 @@
 
 use std::cell::RefCell;
 use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 
 use anyhow::{anyhow, Context as _};
 use cid::{multihash, Cid};
@@ -15,6 +16,7 @@ use fvm_shared::econ::TokenAmount;
 use fvm_shared::state::{StateInfo0, StateRoot, StateTreeVersion};
 use fvm_shared::{ActorID, HAMT_BIT_WIDTH};
 use num_traits::Zero;
+use serde::de::DeserializeOwned;
 
 use crate::init_actor::State as InitActorState;
 use crate::kernel::{ClassifyResult, Context as _, ExecutionError, Result};
@@ -509,6 +511,156 @@ where
     }
 }
 
+/// A read-only view of the state tree at a fixed (usually historical) state root.
+///
+/// Views are independent of any machine's current state tree and of any executing message, so
+/// they can be used to answer "lookback" queries without constructing a new machine.
+///
+/// Actor lookups and address resolutions are cached in a [`StateTreeViewCache`], which can be
+/// shared between views (see [`StateTreeView::with_cache`]). Views handed out by a machine share
+/// the machine's cache, so re-opening a view at the same root doesn't start from a cold cache. The
+/// cache isn't shared with the machine's own state tree.
+pub struct StateTreeView<S> {
+    root: Cid,
+    tree: StateTree<S>,
+    cache: Arc<StateTreeViewCache>,
+}
+
+/// Actor lookups and address resolutions, keyed by state root, shared between
+/// [`StateTreeView`]s.
+///
+/// State roots are immutable, so entries never need to be invalidated.
+#[derive(Default)]
+pub struct StateTreeViewCache {
+    actors: Mutex<HashMap<(Cid, ActorID), Option<ActorState>>>,
+    resolved: Mutex<HashMap<(Cid, Address), Option<ActorID>>>,
+}
+
+impl StateTreeViewCache {
+    /// Number of cached actor lookups.
+    #[cfg(test)]
+    pub(crate) fn cached_actors(&self) -> usize {
+        self.actors.lock().expect("actor cache poisoned").len()
+    }
+}
+
+impl<S> StateTreeView<S>
+where
+    S: Blockstore,
+{
+    /// Opens a view of the state tree with the given root, with its own cache.
+    pub fn new(store: S, root: &Cid) -> Result<Self> {
+        Self::with_cache(store, root, Default::default())
+    }
+
+    /// Opens a view of the state tree with the given root, sharing the given cache.
+    pub fn with_cache(store: S, root: &Cid, cache: Arc<StateTreeViewCache>) -> Result<Self> {
+        Ok(Self {
+            root: *root,
+            tree: StateTree::new_from_root(store, root)?,
+            cache,
+        })
+    }
+
+    /// Returns the state root this view was opened at.
+    pub fn root(&self) -> &Cid {
+        &self.root
+    }
+
+    /// Returns the underlying blockstore.
+    pub fn store(&self) -> &S {
+        self.tree.store()
+    }
+
+    /// Returns the cache this view shares.
+    #[cfg(test)]
+    pub(crate) fn cache(&self) -> &StateTreeViewCache {
+        &self.cache
+    }
+
+    /// Get actor state from an address. Will be resolved to ID address.
+    pub fn get_actor(&self, addr: &Address) -> Result<Option<ActorState>> {
+        let id = match self.lookup_id(addr)? {
+            Some(id) => id,
+            None => return Ok(None),
+        };
+        self.get_actor_id(id)
+    }
+
+    /// Get actor state from an actor ID.
+    pub fn get_actor_id(&self, id: ActorID) -> Result<Option<ActorState>> {
+        let key = (self.root, id);
+        if let Some(act) = self
+            .cache
+            .actors
+            .lock()
+            .expect("actor cache poisoned")
+            .get(&key)
+        {
+            return Ok(act.clone());
+        }
+        let act = self.tree.get_actor_id(id)?;
+        self.cache
+            .actors
+            .lock()
+            .expect("actor cache poisoned")
+            .insert(key, act.clone());
+        Ok(act)
+    }
+
+    /// Get an ID address from any Address.
+    pub fn lookup_id(&self, addr: &Address) -> Result<Option<ActorID>> {
+        if let &Payload::ID(id) = addr.payload() {
+            return Ok(Some(id));
+        }
+        let key = (self.root, *addr);
+        if let Some(id) = self
+            .cache
+            .resolved
+            .lock()
+            .expect("address cache poisoned")
+            .get(&key)
+        {
+            return Ok(*id);
+        }
+        let id = self.tree.lookup_id(addr)?;
+        self.cache
+            .resolved
+            .lock()
+            .expect("address cache poisoned")
+            .insert(key, id);
+        Ok(id)
+    }
+
+    /// Loads and decodes the state object of the given actor, returning `None` if the actor
+    /// doesn't exist.
+    pub fn get_actor_state<T>(&self, id: ActorID) -> Result<Option<T>>
+    where
+        T: DeserializeOwned,
+    {
+        let act = match self.get_actor_id(id)? {
+            Some(act) => act,
+            None => return Ok(None),
+        };
+        let state = self
+            .store()
+            .get_cbor(&act.state)
+            .with_context(|| format!("failed to load state for actor {}", id))
+            .or_fatal()?
+            .with_context(|| format!("state for actor {} not found", id))
+            .or_fatal()?;
+        Ok(Some(state))
+    }
+
+    /// Iterates over all actors in the state tree.
+    pub fn for_each<F>(&self, f: F) -> anyhow::Result<()>
+    where
+        F: FnMut(Address, &ActorState) -> anyhow::Result<()>,
+    {
+        self.tree.for_each(f)
+    }
+}
+
 /// State of all actor implementations.
 #[derive(PartialEq, Eq, Clone, Debug, Serialize_tuple, Deserialize_tuple)]
 pub struct ActorState {
@@ -666,7 +818,7 @@ mod tests {
 
     use crate::init_actor;
     use crate::init_actor::INIT_ACTOR_ADDR;
-    use crate::state_tree::{ActorState, StateTree};
+    use crate::state_tree::{ActorState, StateTree, StateTreeView};
 
     lazy_static! {
         pub static ref DUMMY_ACCOUNT_ACTOR_CODE_ID: Cid = Cid::new_v1(
@@ -764,6 +916,64 @@ mod tests {
         assert_eq!(assigned_addr, 100);
     }
 
+    #[test]
+    fn historical_views() {
+        let store = MemoryBlockstore::default();
+        let mut tree = StateTree::new(&store, StateTreeVersion::V4).unwrap();
+        let init_state = init_actor::State::new_test(&store);
+        let state_cid = store.put_cbor(&init_state, Blake2b256).unwrap();
+        tree.set_actor(
+            &INIT_ACTOR_ADDR,
+            ActorState::new(
+                *DUMMY_INIT_ACTOR_CODE_ID,
+                state_cid,
+                Default::default(),
+                1,
+                None,
+            ),
+        )
+        .unwrap();
+
+        let addr = Address::new_secp256k1(&[2; SECP_PUB_LEN]).unwrap();
+        let id = tree.register_new_address(&addr).unwrap();
+        let act_s = ActorState::new(empty_cid(), state_cid, Default::default(), 1, None);
+        tree.set_actor_id(id, act_s.clone()).unwrap();
+        let old_root = tree.flush().unwrap();
+
+        tree.mutate_actor_id(id, |actor| {
+            actor.sequence = 2;
+            Ok(())
+        })
+        .unwrap();
+        let new_root = tree.flush().unwrap();
+
+        let old = StateTreeView::new(&store, &old_root).unwrap();
+        let new = StateTreeView::new(&store, &new_root).unwrap();
+        assert_eq!(old.root(), &old_root);
+        assert_eq!(new.root(), &new_root);
+
+        assert_eq!(old.lookup_id(&addr).unwrap(), Some(id));
+        assert_eq!(old.get_actor(&addr).unwrap(), Some(act_s));
+        assert_eq!(new.get_actor(&addr).unwrap().unwrap().sequence, 2);
+        let loaded: init_actor::State = old.get_actor_state(id).unwrap().unwrap();
+        assert_eq!(loaded.next_id, init_state.next_id);
+        assert!(old
+            .get_actor_state::<init_actor::State>(id + 1)
+            .unwrap()
+            .is_none());
+
+        let mut count = 0;
+        new.for_each(|_, _| {
+            count += 1;
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(count, 2);
+
+        // Opening a view at a root that doesn't exist fails.
+        assert!(StateTreeView::new(&store, &empty_cid()).is_err());
+    }
+
     #[test]
     fn test_transactions() {
         let store = MemoryBlockstore::default();
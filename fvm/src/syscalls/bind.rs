@@ -124,9 +124,9 @@ macro_rules! impl_bind_syscalls {
                         charge_for_exec(&mut caller)?;
 
                         let (mut memory, mut data) = memory_and_data(&mut caller);
-                        charge_syscall_gas!(data.kernel);
+                        charge_syscall_gas!(data.kernel_mut());
 
-                        let ctx = Context{kernel: &mut data.kernel, memory: &mut memory};
+                        let ctx = Context{kernel: data.kernel_mut(), memory: &mut memory};
                         let out = syscall(ctx $(, $t)*).into();
 
                         let result = match out {
@@ -154,7 +154,7 @@ macro_rules! impl_bind_syscalls {
                         charge_for_exec(&mut caller)?;
 
                         let (mut memory, mut data) = memory_and_data(&mut caller);
-                        charge_syscall_gas!(data.kernel);
+                        charge_syscall_gas!(data.kernel_mut());
 
                         // We need to check to make sure we can store the return value _before_ we do anything.
                         if (ret as u64) > (memory.len() as u64)
@@ -164,7 +164,7 @@ macro_rules! impl_bind_syscalls {
                             return Ok(code as u32);
                         }
 
-                        let ctx = Context{kernel: &mut data.kernel, memory: &mut memory};
+                        let ctx = Context{kernel: data.kernel_mut(), memory: &mut memory};
                         let result = match syscall(ctx $(, $t)*).into() {
                             Ok(Ok(value)) => {
                                 log::trace!("syscall {}::{}: ok", module, name);
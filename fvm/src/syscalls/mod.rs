@@ -26,8 +26,9 @@ pub(self) use context::Context;
 
 /// Invocation data attached to a wasm "store" and available to the syscall binding.
 pub struct InvocationData<K> {
-    /// The kernel on which this actor is being executed.
-    pub kernel: K,
+    /// The kernel on which this actor is being executed. This is only `None` while the
+    /// invocation's instance is idle, waiting to be recycled.
+    pub kernel: Option<K>,
 
     /// The last-seen syscall error. This error is considered the abort "cause" if an actor aborts
     /// after receiving this error without calling any other syscalls.
@@ -45,11 +46,31 @@ pub struct InvocationData<K> {
     pub memory: Memory,
 }
 
+impl<K> InvocationData<K> {
+    /// Returns the kernel on which this actor is being executed.
+    ///
+    /// Panics if no kernel is attached.
+    pub fn kernel(&self) -> &K {
+        self.kernel
+            .as_ref()
+            .expect("no kernel attached to invocation")
+    }
+
+    /// Returns a mutable reference to the kernel on which this actor is being executed.
+    ///
+    /// Panics if no kernel is attached.
+    pub fn kernel_mut(&mut self) -> &mut K {
+        self.kernel
+            .as_mut()
+            .expect("no kernel attached to invocation")
+    }
+}
+
 pub fn update_gas_available(
     ctx: &mut impl AsContextMut<Data = InvocationData<impl Kernel>>,
 ) -> Result<(), Abort> {
     let mut ctx = ctx.as_context_mut();
-    let avail_milligas = ctx.data_mut().kernel.gas_available().as_milligas();
+    let avail_milligas = ctx.data().kernel().gas_available().as_milligas();
 
     let gas_global = ctx.data_mut().avail_gas_global;
     gas_global
@@ -82,7 +103,7 @@ pub fn charge_for_exec(
     };
 
     ctx.data_mut()
-        .kernel
+        .kernel_mut()
         .charge_gas("wasm_exec", Gas::from_milligas(milligas_used))
         .map_err(Abort::from_error_as_fatal)?;
 
@@ -85,6 +85,42 @@ pub trait Buffered: Blockstore {
     fn flush(&self, root: &Cid) -> Result<()>;
 }
 
+/// A blockstore that can enumerate and delete the blocks it holds.
+///
+/// This is required to garbage collect a blockstore in-place.
+pub trait Sweepable: Blockstore {
+    /// Calls `f` with the CID of every block in the blockstore. Stops at the first error.
+    ///
+    /// `f` may delete blocks (including the current one) from the blockstore.
+    fn for_each_key<F>(&self, f: F) -> Result<()>
+    where
+        F: FnMut(&Cid) -> Result<()>;
+
+    /// Deletes the blocks with the given CIDs. Blocks that don't exist are ignored.
+    fn delete_many_keyed<I>(&self, keys: I) -> Result<()>
+    where
+        I: IntoIterator<Item = Cid>;
+}
+
+impl<BS> Sweepable for &BS
+where
+    BS: Sweepable,
+{
+    fn for_each_key<F>(&self, f: F) -> Result<()>
+    where
+        F: FnMut(&Cid) -> Result<()>,
+    {
+        (*self).for_each_key(f)
+    }
+
+    fn delete_many_keyed<I>(&self, keys: I) -> Result<()>
+    where
+        I: IntoIterator<Item = Cid>,
+    {
+        (*self).delete_many_keyed(keys)
+    }
+}
+
 impl<BS> Blockstore for &BS
 where
     BS: Blockstore,
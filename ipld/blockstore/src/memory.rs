@@ -4,7 +4,7 @@ use std::collections::HashMap;
 use anyhow::Result;
 use cid::Cid;
 
-use super::Blockstore;
+use super::{Blockstore, Sweepable};
 
 #[derive(Debug, Default, Clone)]
 pub struct MemoryBlockstore {
@@ -31,3 +31,25 @@ impl Blockstore for MemoryBlockstore {
         Ok(())
     }
 }
+
+impl Sweepable for MemoryBlockstore {
+    fn for_each_key<F>(&self, f: F) -> Result<()>
+    where
+        F: FnMut(&Cid) -> Result<()>,
+    {
+        // Collect the keys up-front so the callback is free to access the store.
+        let keys: Vec<Cid> = self.blocks.borrow().keys().copied().collect();
+        keys.iter().try_for_each(f)
+    }
+
+    fn delete_many_keyed<I>(&self, keys: I) -> Result<()>
+    where
+        I: IntoIterator<Item = Cid>,
+    {
+        let mut blocks = self.blocks.borrow_mut();
+        for k in keys {
+            blocks.remove(&k);
+        }
+        Ok(())
+    }
+}
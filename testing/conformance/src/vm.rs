@@ -9,7 +9,7 @@ use fvm::kernel::*;
 use fvm::machine::{
     DefaultMachine, Engine, Machine, MachineContext, Manifest, MultiEngine, NetworkConfig,
 };
-use fvm::state_tree::{ActorState, StateTree};
+use fvm::state_tree::{ActorState, StateTree, StateTreeView};
 use fvm::DefaultKernel;
 use fvm_ipld_blockstore::MemoryBlockstore;
 use fvm_ipld_car::load_car_unchecked;
@@ -170,6 +170,10 @@ where
         self.machine.flush()
     }
 
+    fn state_tree_at(&self, root: &Cid) -> Result<StateTreeView<&Self::Blockstore>> {
+        self.machine.state_tree_at(root)
+    }
+
     fn machine_id(&self) -> &str {
         self.machine.machine_id()
     }
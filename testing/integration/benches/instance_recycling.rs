@@ -0,0 +1,131 @@
+//! Compares the cost of repeatedly invoking the same actor within a message, with and without
+//! instance recycling, for a range of actor memory sizes.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use fvm::executor::{ApplyKind, Executor};
+use fvm_integration_tests::dummy::DummyExterns;
+use fvm_integration_tests::tester::{Account, IntegrationExecutor};
+use fvm_ipld_blockstore::MemoryBlockstore;
+use fvm_shared::address::Address;
+use fvm_shared::econ::TokenAmount;
+use fvm_shared::error::ExitCode;
+use fvm_shared::message::Message;
+use fvm_shared::state::StateTreeVersion;
+use fvm_shared::version::NetworkVersion;
+use num_traits::Zero;
+use wabt::wat2wasm;
+
+#[path = "../tests/bundles/mod.rs"]
+mod bundles;
+use bundles::*;
+
+/// Number of times the caller invokes the callee per message.
+const CALLS: u32 = 16;
+
+/// Calls f010001 [`CALLS`] times.
+fn caller_wat() -> String {
+    format!(
+        r#"
+(module
+  (import "send" "send" (func $send (param i32 i32 i32 i64 i32 i64 i64) (result i32)))
+  (memory (export "memory") 1)
+  (data (i32.const 768) "\00\91\4e")
+  (func (export "invoke") (param $params i32) (result i32)
+    (local $i i32)
+    (loop $calls
+      (drop (call $send (i32.const 640) (i32.const 768) (i32.const 3) (i64.const 1)
+              (i32.const 0) (i64.const 0) (i64.const 0)))
+      (br_if $calls
+        (i32.lt_u (local.tee $i (i32.add (local.get $i) (i32.const 1))) (i32.const {CALLS}))))
+    (i32.const 0)))
+"#
+    )
+}
+
+/// Has a memory of the given number of pages, and dirties one byte per 4KiB of its first page.
+fn callee_wat(pages: u32) -> String {
+    format!(
+        r#"
+(module
+  (memory (export "memory") {pages})
+  (global $g (mut i32) (i32.const 0))
+  (data (i32.const 1024) "initial data")
+  (func (export "invoke") (param $params i32) (result i32)
+    (local $addr i32)
+    (loop $dirty
+      (i32.store8 (local.get $addr) (i32.const 1))
+      (br_if $dirty
+        (i32.lt_u (local.tee $addr (i32.add (local.get $addr) (i32.const 4096))) (i32.const 65536))))
+    (global.set $g (i32.const 1))
+    (i32.const 0)))
+"#
+    )
+}
+
+fn setup(
+    pages: u32,
+    recycle: bool,
+) -> (IntegrationExecutor<MemoryBlockstore, DummyExterns>, Address) {
+    let mut tester = new_tester(
+        NetworkVersion::V18,
+        StateTreeVersion::V4,
+        MemoryBlockstore::default(),
+    )
+    .unwrap();
+
+    let sender: [Account; 1] = tester.create_accounts().unwrap();
+    let state_cid = tester.set_state(&()).unwrap();
+
+    let caller = wat2wasm(caller_wat()).unwrap();
+    let callee = wat2wasm(callee_wat(pages)).unwrap();
+    for (id, wasm) in [(10000, caller), (10001, callee)] {
+        tester
+            .set_actor_from_bin(&wasm, state_cid, Address::new_id(id), TokenAmount::zero())
+            .unwrap();
+    }
+
+    tester
+        .instantiate_machine_with_config(DummyExterns, |mc| {
+            if !recycle {
+                mc.disable_instance_recycling();
+            }
+        })
+        .unwrap();
+
+    (tester.executor.unwrap(), sender[0].1)
+}
+
+fn bench_recycling(c: &mut Criterion) {
+    let mut group = c.benchmark_group("instance recycling");
+    for pages in [1, 16, 64] {
+        for recycle in [false, true] {
+            let (mut executor, sender) = setup(pages, recycle);
+            let mut sequence = 0;
+            let id = BenchmarkId::new(
+                if recycle { "recycled" } else { "fresh" },
+                format!("{pages} pages"),
+            );
+            group.bench_function(id, |b| {
+                b.iter(|| {
+                    let message = Message {
+                        from: sender,
+                        to: Address::new_id(10000),
+                        gas_limit: 1_000_000_000,
+                        method_num: 1,
+                        sequence,
+                        ..Message::default()
+                    };
+                    sequence += 1;
+                    let res = executor
+                        .execute_message(message, ApplyKind::Explicit, 100)
+                        .unwrap();
+                    assert_eq!(res.msg_receipt.exit_code, ExitCode::OK);
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_recycling);
+criterion_main!(benches);
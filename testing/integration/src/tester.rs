@@ -3,7 +3,7 @@ use cid::Cid;
 use fvm::call_manager::DefaultCallManager;
 use fvm::executor::DefaultExecutor;
 use fvm::externs::Externs;
-use fvm::machine::{DefaultMachine, Engine, Machine, NetworkConfig};
+use fvm::machine::{DefaultMachine, Engine, Machine, MachineContext, NetworkConfig};
 use fvm::state_tree::{ActorState, StateTree};
 use fvm::{init_actor, system_actor, DefaultKernel};
 use fvm_ipld_blockstore::{Block, Blockstore};
@@ -193,6 +193,15 @@ where
 
     /// Sets the Machine and the Executor in our Tester structure.
     pub fn instantiate_machine(&mut self, externs: E) -> Result<()> {
+        self.instantiate_machine_with_config(externs, |_| ())
+    }
+
+    /// Like [`Tester::instantiate_machine`], but lets the caller adjust the machine context
+    /// before the machine is created.
+    pub fn instantiate_machine_with_config<F>(&mut self, externs: E, configure: F) -> Result<()>
+    where
+        F: FnOnce(&mut MachineContext),
+    {
         // Take the state tree and leave None behind.
         let mut state_tree = self.state_tree.take().unwrap();
 
@@ -212,6 +221,7 @@ where
 
         let mut mc = nc.for_epoch(0, state_root);
         mc.set_base_fee(TokenAmount::from_atto(DEFAULT_BASE_FEE));
+        configure(&mut mc);
 
         let machine = DefaultMachine::new(
             &Engine::new_default((&mc.network.clone()).into())?,
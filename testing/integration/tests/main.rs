@@ -8,7 +8,10 @@ use fil_hello_world_actor::WASM_BINARY as HELLO_BINARY;
 use fil_ipld_actor::WASM_BINARY as IPLD_BINARY;
 use fil_stack_overflow_actor::WASM_BINARY as OVERFLOW_BINARY;
 use fil_syscall_actor::WASM_BINARY as SYSCALL_BINARY;
-use fvm::executor::{ApplyKind, Executor, ThreadedExecutor};
+use fvm::call_manager::backtrace::Cause;
+use fvm::executor::{ApplyFailure, ApplyKind, ApplyRet, Executor, ThreadedExecutor};
+use fvm::gas::Gas;
+use fvm::machine::Machine;
 use fvm_integration_tests::dummy::DummyExterns;
 use fvm_integration_tests::tester::{Account, IntegrationExecutor};
 use fvm_ipld_blockstore::{Blockstore, MemoryBlockstore};
@@ -247,7 +250,7 @@ fn native_stack_overflow() {
     assert_eq!(exec_test(&mut executor, 3), 0x80000042);
 }
 
-fn test_exitcode(wat: &str, code: ExitCode) {
+fn exec_wat(wat: &str) -> ApplyRet {
     // Instantiate tester
     let mut tester = new_tester(
         NetworkVersion::V18,
@@ -285,23 +288,46 @@ fn test_exitcode(wat: &str, code: ExitCode) {
     };
 
     let mut executor = ThreadedExecutor(tester.executor.unwrap());
-    let res = executor
+    executor
         .execute_message(message, ApplyKind::Explicit, 100)
-        .unwrap();
+        .unwrap()
+}
 
+fn test_exitcode(wat: &str, code: ExitCode) {
+    let res = exec_wat(wat);
     assert_eq!(res.msg_receipt.exit_code, code)
 }
 
 #[test]
 fn out_of_gas() {
-    test_exitcode(
+    let res = exec_wat(
         r#"(module
              (memory (export "memory") 1)
              (func (export "invoke") (param $x i32) (result i32)
                (loop (br 0))
                (i32.const 1)))"#,
-        ExitCode::SYS_OUT_OF_GAS,
-    )
+    );
+    assert_eq!(res.msg_receipt.exit_code, ExitCode::SYS_OUT_OF_GAS);
+
+    // The failure should tell us which charge ran out of gas, and where.
+    match res.failure_info.as_ref().unwrap() {
+        ApplyFailure::MessageBacktrace(backtrace) => match backtrace.cause.as_ref().unwrap() {
+            Cause::OutOfGas {
+                charge_name,
+                gas_required,
+                gas_limit,
+                depth,
+                ..
+            } => {
+                assert_eq!(charge_name, "wasm_exec");
+                assert_eq!(*depth, 1);
+                assert_eq!(*gas_limit, Gas::new(10_000_000));
+                assert!(gas_required > gas_limit);
+            }
+            _ => panic!("failure cause should be out of gas"),
+        },
+        _ => panic!("transaction result should have a backtrace"),
+    }
 }
 
 #[test]
@@ -345,6 +371,146 @@ fn out_of_stack() {
     );
 }
 
+/// An actor that checks that each invocation starts from a fresh instance (aborting with exit code
+/// 42 otherwise), then dirties its memory and globals. Depending on the method number, it then
+/// calls another actor with the same code (f010001) or itself (f010000):
+///
+/// 1. Nothing.
+/// 2. Calls f010001 twice.
+/// 3. Calls itself twice, one call after the other.
+/// 4. Calls itself with methods 2, 3, and 3.
+const RECYCLE_WAT: &str = r#"
+(module
+  (import "vm" "context" (func $context (param i32) (result i32)))
+  (import "vm" "abort" (func $abort (param i32 i32 i32) (result i32)))
+  (import "send" "send" (func $send (param i32 i32 i32 i64 i32 i64 i64) (result i32)))
+  (memory (export "memory") 1)
+  ;; Set by every invocation.
+  (global $dirty (mut i32) (i32.const 0))
+  ;; Overwritten by every invocation.
+  (data (i32.const 0) "\01")
+  ;; The ID addresses of this actor (f010000) and the other actor (f010001).
+  (data (i32.const 768) "\00\90\4e")
+  (data (i32.const 776) "\00\91\4e")
+
+  (func $fail
+    (drop (call $abort (i32.const 42) (i32.const 0) (i32.const 0)))
+    unreachable)
+
+  ;; Sends a message to the address at $addr, failing unless it succeeds.
+  (func $call (param $addr i32) (param $method i64)
+    (if (call $send (i32.const 640) (local.get $addr) (i32.const 3) (local.get $method)
+          (i32.const 0) (i64.const 0) (i64.const 0))
+      (then (call $fail)))
+    (if (i32.load (i32.const 640))
+      (then (call $fail))))
+
+  (func (export "invoke") (param $params i32) (result i32)
+    (local $method i64)
+    (if (i32.or (global.get $dirty)
+          (i32.or (i32.ne (i32.load8_u (i32.const 0)) (i32.const 1))
+                  (i32.load8_u (i32.const 60000))))
+      (then (call $fail)))
+    (global.set $dirty (i32.const 1))
+    (i32.store8 (i32.const 0) (i32.const 2))
+    (i32.store8 (i32.const 60000) (i32.const 7))
+
+    ;; Load the method number from the invocation context.
+    (if (call $context (i32.const 512))
+      (then (call $fail)))
+    (local.set $method (i64.load (i32.const 544)))
+
+    (if (i64.eq (local.get $method) (i64.const 2))
+      (then
+        (call $call (i32.const 776) (i64.const 1))
+        (call $call (i32.const 776) (i64.const 1))))
+    (if (i64.eq (local.get $method) (i64.const 3))
+      (then
+        (call $call (i32.const 768) (i64.const 1))
+        (call $call (i32.const 768) (i64.const 1))))
+    (if (i64.eq (local.get $method) (i64.const 4))
+      (then
+        (call $call (i32.const 768) (i64.const 2))
+        (call $call (i32.const 768) (i64.const 3))
+        (call $call (i32.const 768) (i64.const 3))))
+    (i32.const 0)))
+"#;
+
+/// Sends a message with the given method to the [`RECYCLE_WAT`] actor, returning the result along
+/// with the number of instances created.
+fn exec_recycle(method: u64, recycle: bool) -> (ApplyRet, u64) {
+    let mut tester = new_tester(
+        NetworkVersion::V18,
+        StateTreeVersion::V4,
+        MemoryBlockstore::default(),
+    )
+    .unwrap();
+
+    let sender: [Account; 1] = tester.create_accounts().unwrap();
+
+    let wasm_bin = wat2wasm(RECYCLE_WAT).unwrap();
+    let state_cid = tester.set_state(&State { count: 0 }).unwrap();
+    for id in [10000, 10001] {
+        tester
+            .set_actor_from_bin(
+                &wasm_bin,
+                state_cid,
+                Address::new_id(id),
+                TokenAmount::zero(),
+            )
+            .unwrap();
+    }
+
+    tester
+        .instantiate_machine_with_config(DummyExterns, |mc| {
+            if !recycle {
+                mc.disable_instance_recycling();
+            }
+        })
+        .unwrap();
+
+    let message = Message {
+        from: sender[0].1,
+        to: Address::new_id(10000),
+        gas_limit: 1_000_000_000,
+        method_num: method,
+        ..Message::default()
+    };
+
+    let mut executor = tester.executor.unwrap();
+    let res = executor
+        .execute_message(message, ApplyKind::Explicit, 100)
+        .unwrap();
+    (res, executor.engine().instantiation_count())
+}
+
+/// Checks that recycled instances start from a fresh state, and that recycling doesn't affect gas
+/// usage.
+fn test_recycling(method: u64) {
+    let (fresh, fresh_instances) = exec_recycle(method, false);
+    let (recycled, recycled_instances) = exec_recycle(method, true);
+
+    assert_eq!(fresh.msg_receipt.exit_code, ExitCode::OK);
+    assert_eq!(recycled.msg_receipt.exit_code, ExitCode::OK);
+    assert!(recycled_instances < fresh_instances);
+    assert_eq!(recycled.msg_receipt.gas_used, fresh.msg_receipt.gas_used);
+}
+
+#[test]
+fn recycle_instances() {
+    test_recycling(2);
+}
+
+#[test]
+fn recycle_instances_self_calls() {
+    test_recycling(3);
+}
+
+#[test]
+fn recycle_instances_nested() {
+    test_recycling(4);
+}
+
 #[test]
 fn backtraces() {
     // Note: this test **does not actually assert anything**, but it's useful to